@@ -0,0 +1,53 @@
+/// Helper constructors for building `Expression` trees concisely
+/// These are mostly useful when writing the expected result of a parse by hand, which otherwise needs a `Box::new` and a `to_string` on every node.
+use crate::statement::{Expression, BinaryOperator, UnaryOperator};
+
+/// Builds an `Expression::Identifier`
+pub fn ident(name: &str) -> Expression {
+    Expression::Identifier(name.to_string())
+}
+
+/// Builds an `Expression::Number`
+pub fn num(value: i64) -> Expression {
+    Expression::Number(value)
+}
+
+/// Builds an `Expression::String`
+pub fn string(value: &str) -> Expression {
+    Expression::String(value.to_string())
+}
+
+/// Builds an `Expression::Bool`
+pub fn boolean(value: bool) -> Expression {
+    Expression::Bool(value)
+}
+
+/// Builds an `Expression::BinaryOperation`
+pub fn binary(left_operand: Expression, operator: BinaryOperator, right_operand: Expression) -> Expression {
+    Expression::BinaryOperation {
+        left_operand: Box::new(left_operand),
+        operator,
+        right_operand: Box::new(right_operand),
+    }
+}
+
+/// Builds an `Expression::UnaryOperation`
+pub fn unary(operator: UnaryOperator, operand: Expression) -> Expression {
+    Expression::UnaryOperation {
+        operand: Box::new(operand),
+        operator,
+    }
+}
+
+/// Builds an `Expression::FunctionCall`
+pub fn call(name: &str, args: Vec<Expression>) -> Expression {
+    Expression::FunctionCall {
+        name: name.to_string(),
+        args,
+    }
+}
+
+/// Builds an `Expression::Row`
+pub fn row(items: Vec<Expression>) -> Expression {
+    Expression::Row(items)
+}
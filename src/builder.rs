@@ -0,0 +1,162 @@
+/// A fluent builder for `SELECT` statements
+/// This lets callers construct a `Statement::Select` in code instead of parsing text, for example to generate SQL through the `Display` output.
+use crate::error::Error;
+use crate::statement::{BinaryOperator, Expression, FromSource, GroupBy, QualifiedName, Statement};
+
+/// Collects the clauses of a `SELECT` one method call at a time. Names are used exactly as given, so pass them uppercased to match what the parser produces for unquoted identifiers.
+#[derive(Debug, Clone, Default)]
+pub struct SelectBuilder {
+    columns: Vec<Expression>,
+    from: Option<FromSource>,
+    r#where: Option<Expression>,
+    groupby: Option<GroupBy>,
+    having: Option<Expression>,
+    orderby: Vec<Expression>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+impl SelectBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an expression to the select list. A builder with no columns selects `*`.
+    pub fn column(mut self, expr: Expression) -> Self {
+        self.columns.push(expr);
+        self
+    }
+
+    /// Reads the rows from the named table
+    pub fn from(mut self, table: &str) -> Self {
+        self.from = Some(FromSource::Table { name: QualifiedName::from(table), only: false, sample: None });
+        self
+    }
+
+    /// Reads the rows from a derived table, `FROM (query) AS alias`
+    pub fn from_subquery(mut self, query: Statement, alias: &str) -> Self {
+        self.from = Some(FromSource::Derived {
+            query: Box::new(query),
+            alias: alias.to_string(),
+        });
+        self
+    }
+
+    /// Sets the `WHERE` condition. Calling it again combines the conditions with `AND`.
+    pub fn filter(mut self, condition: Expression) -> Self {
+        self.r#where = Some(match self.r#where.take() {
+            Some(existing) => Expression::BinaryOperation {
+                left_operand: Box::new(existing),
+                operator: BinaryOperator::And,
+                right_operand: Box::new(condition),
+            },
+            None => condition,
+        });
+        self
+    }
+
+    /// Adds an expression to the `GROUP BY` clause
+    pub fn group_by(mut self, expr: Expression) -> Self {
+        self.groupby
+            .get_or_insert_with(|| GroupBy { expressions: Vec::new(), rollup: false })
+            .expressions
+            .push(expr);
+        self
+    }
+
+    /// Sets the `HAVING` condition
+    pub fn having(mut self, condition: Expression) -> Self {
+        self.having = Some(condition);
+        self
+    }
+
+    /// Adds an item to the `ORDER BY` clause. Wrap it in an `ASC` or `DESC` unary operation to give a direction.
+    pub fn order_by(mut self, item: Expression) -> Self {
+        self.orderby.push(item);
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Builds the statement
+    ///
+    /// # Returns
+    /// The `Statement::Select`, or `Error::MissingFromClause` if neither `from` nor `from_subquery` was called
+    pub fn build(self) -> Result<Statement, Error> {
+        let from = self.from.ok_or(Error::MissingFromClause)?;
+        let columns = if self.columns.is_empty() {
+            vec![Expression::Identifier("*".to_string())]
+        } else {
+            self.columns
+        };
+        Ok(Statement::Select {
+            columns,
+            from,
+            r#where: self.r#where,
+            groupby: self.groupby,
+            having: self.having,
+            orderby: self.orderby,
+            limit: self.limit,
+            offset: self.offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{binary, call, ident, num, unary};
+    use crate::parse;
+    use crate::statement::UnaryOperator;
+
+    #[test]
+    fn test_build_select_matches_parse() {
+        let stmt = SelectBuilder::new()
+            .column(ident("ID"))
+            .column(ident("NAME"))
+            .from("USERS")
+            .filter(binary(ident("AGE"), BinaryOperator::GreaterThan, num(18)))
+            .filter(binary(ident("ACTIVE"), BinaryOperator::Equal, Expression::Bool(true)))
+            .order_by(unary(UnaryOperator::Desc, ident("NAME")))
+            .limit(10)
+            .build()
+            .unwrap();
+        assert_eq!(
+            stmt,
+            parse("SELECT id, name FROM users WHERE age > 18 AND active = TRUE ORDER BY name DESC LIMIT 10;").unwrap()
+        );
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_build_grouped_select_and_wildcard() {
+        let stmt = SelectBuilder::new()
+            .column(ident("REGION"))
+            .column(call("SUM", vec![ident("AMOUNT")]))
+            .from("SALES")
+            .group_by(ident("REGION"))
+            .having(binary(call("SUM", vec![ident("AMOUNT")]), BinaryOperator::GreaterThan, num(100)))
+            .build()
+            .unwrap();
+        assert_eq!(
+            stmt,
+            parse("SELECT region, SUM(amount) FROM sales GROUP BY region HAVING SUM(amount) > 100;").unwrap()
+        );
+
+        let stmt = SelectBuilder::new().from("T").offset(5).build().unwrap();
+        assert_eq!(stmt, parse("SELECT * FROM t OFFSET 5;").unwrap());
+    }
+
+    #[test]
+    fn test_build_without_from() {
+        assert!(matches!(SelectBuilder::new().column(num(1)).build(), Err(Error::MissingFromClause)));
+    }
+}
@@ -0,0 +1,28 @@
+//! SQL dialects the tokenizer and parser can be configured for
+//! The dialects agree on almost everything this parser supports; where they differ, the difference is looked up here rather than checked by name at every call site.
+
+/// The flavor of SQL being parsed. `Generic` is the default and keeps the behavior the parser had before dialects existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    #[default]
+    Generic,
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    /// Returns the character that quotes an identifier, if the dialect has one. A quoted identifier keeps its case and may be a reserved word.
+    pub fn identifier_quote(&self) -> Option<char> {
+        match self {
+            Dialect::Generic => None,
+            Dialect::Postgres | Dialect::Sqlite => Some('"'),
+            Dialect::MySql => Some('`'),
+        }
+    }
+
+    /// Returns whether string literals use backslash escapes by default, as MySQL does
+    pub fn backslash_escapes(&self) -> bool {
+        matches!(self, Dialect::MySql)
+    }
+}
@@ -1,8 +1,10 @@
 /// Error types for the SQL Parser
 /// This module defines all possible errors that can occur during lexing and parsing.
 use thiserror::Error;
+use crate::token::Span;
 
 /// Represents all possible errors in the SQL Parser
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum Error {
     /// Error during lexical analysis (tokenization)
@@ -14,18 +16,26 @@ pub enum Error {
     ParserError(String),
     
     /// Unexpected token encountered during parsing
-    #[error("Unexpected token: expected {expected}, found {found}")]
+    #[error("Unexpected token: expected {expected}, found {found}{} near: {context}", format_position(.position))]
     UnexpectedToken {
         /// What was expected by the parser
         expected: String,
         /// What was actually found in the input
         found: String,
+        /// Where the unexpected token starts, when the parser knows the token positions
+        position: Option<Span>,
+        /// The tokens around the unexpected one, with the unexpected token marked as `>>> token <<<`
+        context: String,
     },
     
     /// Unexpected end of input
     #[error("Unexpected end of input")]
     UnexpectedEOF,
 
+    /// The input contained no tokens at all, so there was no statement to parse
+    #[error("Empty input")]
+    EmptyInput,
+
     #[error("Invalid VARCHAR length: {0}")]
     InvalidVarcharLength(String),
 
@@ -34,4 +44,23 @@ pub enum Error {
 
     #[error("Invalid FOREIGN KEY constraint: {0}")]
     InvalidForeignKey(String),
-} 
\ No newline at end of file
+
+    /// A statement refers to a table the schema does not have
+    #[error("Unknown table: {0}")]
+    UnknownTable(String),
+
+    /// A statement compares values that can never match, found by `Statement::type_check`
+    #[error("Type mismatch: {0}")]
+    TypeMismatch(String),
+
+    /// An aggregate function is used where it cannot be evaluated, found by `Statement::validate_aggregates`
+    #[error("Misplaced aggregate: {0}")]
+    MisplacedAggregate(String),
+}
+
+fn format_position(position: &Option<Span>) -> String {
+    match position {
+        Some(span) => format!(" at {}", span),
+        None => String::new(),
+    }
+}
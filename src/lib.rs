@@ -0,0 +1,129 @@
+//! SQL parser library
+//! This crate turns SQL query strings into `Statement` values. The REPL in `main.rs` is a thin wrapper around it.
+pub mod ast;
+pub mod builder;
+pub mod dialect;
+pub mod error;
+pub mod optimize;
+pub mod parser;
+pub mod schema;
+pub mod statement;
+pub mod token;
+pub mod tokenizer;
+
+use dialect::Dialect;
+use error::Error;
+use parser::Parser;
+use statement::{CommentedStatement, Statement};
+use tokenizer::Tokenizer;
+
+/// An iterator over the statements of a script, each terminated by a semicolon. The input is tokenized on the first call to `next`, and each later call parses one more statement. After an error, the error is returned once and the iterator ends.
+pub struct Statements<'a> {
+    tokenizer: Tokenizer<'a>,
+    parser: Option<Parser>,
+    done: bool,
+}
+
+impl<'a> Statements<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            tokenizer: Tokenizer::new(input),
+            parser: None,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Statements<'_> {
+    type Item = Result<Statement, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let parser = match &mut self.parser {
+            Some(parser) => parser,
+            None => match self.tokenizer.tokenize() {
+                Ok(tokens) => self.parser.insert(Parser::new(tokens).with_spans(self.tokenizer.spans().to_vec())),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            },
+        };
+
+        if parser.is_at_end() {
+            self.done = true;
+            return None;
+        }
+        let result = parser.parse_next();
+        self.done = result.is_err();
+        Some(result)
+    }
+}
+
+/// Counts the tokens in the input, not including the end-of-input marker. This is a cheap estimate of how complex a query is, available before it is parsed.
+///
+/// # Arguments
+/// * `input` - The SQL query string to tokenize
+///
+/// # Returns
+/// Result containing either the number of tokens or the Error that stopped tokenization
+pub fn token_count(input: &str) -> Result<usize, Error> {
+    let tokens = Tokenizer::new(input).tokenize()?;
+    Ok(tokens.iter().filter(|token| **token != token::Token::Eof).count())
+}
+
+/// Parses a single SQL statement, which must be terminated by a semicolon
+///
+/// # Arguments
+/// * `input` - The SQL query string to parse
+///
+/// # Returns
+/// Result containing either the parsed Statement or an Error
+pub fn parse(input: &str) -> Result<Statement, Error> {
+    let mut tokenizer = Tokenizer::new(input);
+    let tokens = tokenizer.tokenize()?;
+    Parser::new(tokens).with_spans(tokenizer.spans().to_vec()).parse()
+}
+
+/// Parses a single SQL statement, treating the end of input as a terminating semicolon
+///
+/// # Arguments
+/// * `input` - The SQL query string to parse
+///
+/// # Returns
+/// Result containing either the parsed Statement or an Error
+pub fn parse_relaxed(input: &str) -> Result<Statement, Error> {
+    let mut tokenizer = Tokenizer::new(input);
+    let tokens = tokenizer.tokenize()?;
+    Parser::new(tokens).with_spans(tokenizer.spans().to_vec()).parse_relaxed()
+}
+
+/// Parses a single SQL statement, which must be terminated by a semicolon, keeping the `--` comments written before it
+///
+/// # Arguments
+/// * `input` - The SQL query string to parse
+///
+/// # Returns
+/// Result containing either the parsed statement with its leading comments or an Error
+pub fn parse_with_comments(input: &str) -> Result<CommentedStatement, Error> {
+    let mut tokenizer = Tokenizer::new(input).with_keep_comments(true);
+    let tokens = tokenizer.tokenize()?;
+    Parser::new(tokens).with_spans(tokenizer.spans().to_vec()).parse_with_comments()
+}
+
+/// Parses a single SQL statement written in the given dialect, which must be terminated by a semicolon
+///
+/// # Arguments
+/// * `input` - The SQL query string to parse
+/// * `dialect` - The dialect deciding how quotes and escapes are read
+///
+/// # Returns
+/// Result containing either the parsed Statement or an Error
+pub fn parse_with_dialect(input: &str, dialect: Dialect) -> Result<Statement, Error> {
+    let mut tokenizer = Tokenizer::new_with_dialect(input, dialect);
+    let tokens = tokenizer.tokenize()?;
+    Parser::new(tokens).with_spans(tokenizer.spans().to_vec()).with_dialect(dialect).parse()
+}
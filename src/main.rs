@@ -1,13 +1,8 @@
-mod statement;
-mod token;
-mod tokenizer;
-mod parser;
-mod error;
-
 use std::io::{self, Write};
-use tokenizer::Tokenizer;
-use parser::Parser;
-use error::Error;
+use ezzeldeen_kadri_abdelkader_atta_shahein::tokenizer::Tokenizer;
+use ezzeldeen_kadri_abdelkader_atta_shahein::parser::Parser;
+use ezzeldeen_kadri_abdelkader_atta_shahein::error::Error;
+use ezzeldeen_kadri_abdelkader_atta_shahein::statement;
 
 /// Gets a line of input from the user
 /// 
@@ -28,7 +23,7 @@ fn get_string_from_user() -> String {
 /// * `input` - The SQL query string to parse
 /// 
 /// # Returns
-/// Result containing either the parsed Statement or an Error
+/// Result containing either the parsed Statement or an Error, which is `Error::EmptyInput` if the input holds no tokens
 fn build_statement(input: &str) -> Result<statement::Statement, Error> {
     // Create tokenizer and get tokens
     let mut tokenizer = Tokenizer::new(input);
@@ -38,7 +33,7 @@ fn build_statement(input: &str) -> Result<statement::Statement, Error> {
     };
     
     // Create parser and parse tokens
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(tokens).with_spans(tokenizer.spans().to_vec());
     parser.parse()
 }
 
@@ -51,6 +46,8 @@ fn main() {
 
         match build_statement(&input) {
             Ok(stmt) => println!("Successfully parsed:\n{:#?}", stmt),
+            // Input without any tokens has nothing to report
+            Err(Error::EmptyInput) => {}
             Err(e) => eprintln!("Error: {}", e),
         }
     }
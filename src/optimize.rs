@@ -0,0 +1,578 @@
+/// Rewrites of parsed expressions that keep their meaning but make them simpler
+/// None of these passes need a schema; they only look at the expression tree itself.
+use crate::statement::{Expression, BinaryOperator, UnaryOperator};
+
+/// Evaluates constant sub-expressions, so `2 + 3 * 4` becomes `14`
+///
+/// Column references, parameters and anything else that is only known at execution time are left untouched.
+/// Arithmetic that would fail at execution time (division by zero, overflow, a negative result) is also left as it is, so the database reports the error instead of the optimizer. Negative literals, which only appear after `Expression::simplify_negation`, are not folded either.
+///
+/// # Arguments
+/// * `expr` - The expression to fold
+///
+/// # Returns
+/// The folded expression
+pub fn fold_constants(expr: Expression) -> Expression {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            let left = fold_constants(*left_operand);
+            let right = fold_constants(*right_operand);
+            fold_binary(left, operator, right)
+        }
+        Expression::UnaryOperation { operand, operator } => {
+            let operand = fold_constants(*operand);
+            match (operator, operand) {
+                (UnaryOperator::Plus, Expression::Number(n)) => Expression::Number(n),
+                (UnaryOperator::Not, Expression::Bool(b)) => Expression::Bool(!b),
+                (operator, operand) => Expression::UnaryOperation {
+                    operand: Box::new(operand),
+                    operator,
+                },
+            }
+        }
+        Expression::FunctionCall { name, args } => Expression::FunctionCall {
+            name,
+            args: args.into_iter().map(fold_constants).collect(),
+        },
+        Expression::Row(items) => Expression::Row(items.into_iter().map(fold_constants).collect()),
+        Expression::IsNull { operand, negated } => Expression::IsNull {
+            operand: Box::new(fold_constants(*operand)),
+            negated,
+        },
+        Expression::IsUnknown { operand, negated } => Expression::IsUnknown {
+            operand: Box::new(fold_constants(*operand)),
+            negated,
+        },
+        Expression::InList { expr, list, negated } => Expression::InList {
+            expr: Box::new(fold_constants(*expr)),
+            list: list.into_iter().map(fold_constants).collect(),
+            negated,
+        },
+        Expression::InSubquery { expr, subquery, negated } => Expression::InSubquery {
+            expr: Box::new(fold_constants(*expr)),
+            subquery,
+            negated,
+        },
+        Expression::Quantified { left, op, quantifier, subquery } => Expression::Quantified {
+            left: Box::new(fold_constants(*left)),
+            op,
+            quantifier,
+            subquery,
+        },
+        Expression::Alias { expr, alias } => Expression::Alias {
+            expr: Box::new(fold_constants(*expr)),
+            alias,
+        },
+        Expression::Like { expr, pattern, negated, escape } => Expression::Like {
+            expr: Box::new(fold_constants(*expr)),
+            pattern: Box::new(fold_constants(*pattern)),
+            negated,
+            escape,
+        },
+        Expression::DistinctFrom { left, right, negated } => Expression::DistinctFrom {
+            left: Box::new(fold_constants(*left)),
+            right: Box::new(fold_constants(*right)),
+            negated,
+        },
+        Expression::Between { expr, low, high, negated } => Expression::Between {
+            expr: Box::new(fold_constants(*expr)),
+            low: Box::new(fold_constants(*low)),
+            high: Box::new(fold_constants(*high)),
+            negated,
+        },
+        Expression::Case { operand, branches, else_result } => rewrite_case(operand, branches, else_result, fold_constants),
+        Expression::And(operands) => Expression::And(operands.into_iter().map(fold_constants).collect()),
+        Expression::Or(operands) => Expression::Or(operands.into_iter().map(fold_constants).collect()),
+        other => other,
+    }
+}
+
+/// Collapses chains of `AND` and of `OR` into n-ary nodes, so `a AND b AND c` becomes `And([a, b, c])`
+///
+/// Both operators are associative, so `a AND (b AND c)` collapses to the same node as `(a AND b) AND c`. Chains are flattened wherever they appear, including inside `NOT`, function arguments and null tests.
+///
+/// # Arguments
+/// * `expr` - The expression to flatten
+///
+/// # Returns
+/// The expression with every `AND` and `OR` chain replaced by an `And` or `Or` node
+pub fn flatten_boolean(expr: Expression) -> Expression {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => {
+            let mut operands = Vec::new();
+            push_flattened(&mut operands, flatten_boolean(*left_operand), &BinaryOperator::And);
+            push_flattened(&mut operands, flatten_boolean(*right_operand), &BinaryOperator::And);
+            Expression::And(operands)
+        }
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::Or, right_operand } => {
+            let mut operands = Vec::new();
+            push_flattened(&mut operands, flatten_boolean(*left_operand), &BinaryOperator::Or);
+            push_flattened(&mut operands, flatten_boolean(*right_operand), &BinaryOperator::Or);
+            Expression::Or(operands)
+        }
+        Expression::BinaryOperation { left_operand, operator, right_operand } => Expression::BinaryOperation {
+            left_operand: Box::new(flatten_boolean(*left_operand)),
+            operator,
+            right_operand: Box::new(flatten_boolean(*right_operand)),
+        },
+        Expression::UnaryOperation { operand, operator } => Expression::UnaryOperation {
+            operand: Box::new(flatten_boolean(*operand)),
+            operator,
+        },
+        Expression::FunctionCall { name, args } => Expression::FunctionCall {
+            name,
+            args: args.into_iter().map(flatten_boolean).collect(),
+        },
+        Expression::Row(items) => Expression::Row(items.into_iter().map(flatten_boolean).collect()),
+        Expression::IsNull { operand, negated } => Expression::IsNull {
+            operand: Box::new(flatten_boolean(*operand)),
+            negated,
+        },
+        Expression::IsUnknown { operand, negated } => Expression::IsUnknown {
+            operand: Box::new(flatten_boolean(*operand)),
+            negated,
+        },
+        Expression::InList { expr, list, negated } => Expression::InList {
+            expr: Box::new(flatten_boolean(*expr)),
+            list: list.into_iter().map(flatten_boolean).collect(),
+            negated,
+        },
+        Expression::InSubquery { expr, subquery, negated } => Expression::InSubquery {
+            expr: Box::new(flatten_boolean(*expr)),
+            subquery,
+            negated,
+        },
+        Expression::Quantified { left, op, quantifier, subquery } => Expression::Quantified {
+            left: Box::new(flatten_boolean(*left)),
+            op,
+            quantifier,
+            subquery,
+        },
+        Expression::Alias { expr, alias } => Expression::Alias {
+            expr: Box::new(flatten_boolean(*expr)),
+            alias,
+        },
+        Expression::Like { expr, pattern, negated, escape } => Expression::Like {
+            expr: Box::new(flatten_boolean(*expr)),
+            pattern: Box::new(flatten_boolean(*pattern)),
+            negated,
+            escape,
+        },
+        Expression::DistinctFrom { left, right, negated } => Expression::DistinctFrom {
+            left: Box::new(flatten_boolean(*left)),
+            right: Box::new(flatten_boolean(*right)),
+            negated,
+        },
+        Expression::Between { expr, low, high, negated } => Expression::Between {
+            expr: Box::new(flatten_boolean(*expr)),
+            low: Box::new(flatten_boolean(*low)),
+            high: Box::new(flatten_boolean(*high)),
+            negated,
+        },
+        Expression::Case { operand, branches, else_result } => rewrite_case(operand, branches, else_result, flatten_boolean),
+        Expression::And(operands) => {
+            let mut flattened = Vec::new();
+            for operand in operands {
+                push_flattened(&mut flattened, flatten_boolean(operand), &BinaryOperator::And);
+            }
+            Expression::And(flattened)
+        }
+        Expression::Or(operands) => {
+            let mut flattened = Vec::new();
+            for operand in operands {
+                push_flattened(&mut flattened, flatten_boolean(operand), &BinaryOperator::Or);
+            }
+            Expression::Or(flattened)
+        }
+        other => other,
+    }
+}
+
+/// Adds an already flattened operand to an n-ary node, splicing in its operands if it is a node of the same kind
+fn push_flattened(operands: &mut Vec<Expression>, operand: Expression, operator: &BinaryOperator) {
+    match (operand, operator) {
+        (Expression::And(inner), BinaryOperator::And) | (Expression::Or(inner), BinaryOperator::Or) => operands.extend(inner),
+        (operand, _) => operands.push(operand),
+    }
+}
+
+/// Turns `And` and `Or` nodes back into left-nested binary operations, the shape the parser produces, so `And([a, b, c])` becomes `(a AND b) AND c`
+///
+/// A node with a single operand becomes that operand. An empty `And` is `TRUE` and an empty `Or` is `FALSE`, the identity values of the two operators.
+///
+/// # Arguments
+/// * `expr` - The expression to expand
+///
+/// # Returns
+/// The expression without any `And` or `Or` nodes
+pub fn unflatten_boolean(expr: Expression) -> Expression {
+    match expr {
+        Expression::And(operands) => unflatten_chain(operands, true),
+        Expression::Or(operands) => unflatten_chain(operands, false),
+        Expression::BinaryOperation { left_operand, operator, right_operand } => Expression::BinaryOperation {
+            left_operand: Box::new(unflatten_boolean(*left_operand)),
+            operator,
+            right_operand: Box::new(unflatten_boolean(*right_operand)),
+        },
+        Expression::UnaryOperation { operand, operator } => Expression::UnaryOperation {
+            operand: Box::new(unflatten_boolean(*operand)),
+            operator,
+        },
+        Expression::FunctionCall { name, args } => Expression::FunctionCall {
+            name,
+            args: args.into_iter().map(unflatten_boolean).collect(),
+        },
+        Expression::Row(items) => Expression::Row(items.into_iter().map(unflatten_boolean).collect()),
+        Expression::IsNull { operand, negated } => Expression::IsNull {
+            operand: Box::new(unflatten_boolean(*operand)),
+            negated,
+        },
+        Expression::IsUnknown { operand, negated } => Expression::IsUnknown {
+            operand: Box::new(unflatten_boolean(*operand)),
+            negated,
+        },
+        Expression::InList { expr, list, negated } => Expression::InList {
+            expr: Box::new(unflatten_boolean(*expr)),
+            list: list.into_iter().map(unflatten_boolean).collect(),
+            negated,
+        },
+        Expression::InSubquery { expr, subquery, negated } => Expression::InSubquery {
+            expr: Box::new(unflatten_boolean(*expr)),
+            subquery,
+            negated,
+        },
+        Expression::Quantified { left, op, quantifier, subquery } => Expression::Quantified {
+            left: Box::new(unflatten_boolean(*left)),
+            op,
+            quantifier,
+            subquery,
+        },
+        Expression::Alias { expr, alias } => Expression::Alias {
+            expr: Box::new(unflatten_boolean(*expr)),
+            alias,
+        },
+        Expression::Like { expr, pattern, negated, escape } => Expression::Like {
+            expr: Box::new(unflatten_boolean(*expr)),
+            pattern: Box::new(unflatten_boolean(*pattern)),
+            negated,
+            escape,
+        },
+        Expression::DistinctFrom { left, right, negated } => Expression::DistinctFrom {
+            left: Box::new(unflatten_boolean(*left)),
+            right: Box::new(unflatten_boolean(*right)),
+            negated,
+        },
+        Expression::Between { expr, low, high, negated } => Expression::Between {
+            expr: Box::new(unflatten_boolean(*expr)),
+            low: Box::new(unflatten_boolean(*low)),
+            high: Box::new(unflatten_boolean(*high)),
+            negated,
+        },
+        Expression::Case { operand, branches, else_result } => rewrite_case(operand, branches, else_result, unflatten_boolean),
+        other => other,
+    }
+}
+
+/// Builds the left-nested chain for an `And` node when `is_and` is set, and for an `Or` node otherwise
+fn unflatten_chain(operands: Vec<Expression>, is_and: bool) -> Expression {
+    let mut operands = operands.into_iter().map(unflatten_boolean);
+    let Some(mut chain) = operands.next() else {
+        return Expression::Bool(is_and);
+    };
+    for operand in operands {
+        chain = Expression::BinaryOperation {
+            left_operand: Box::new(chain),
+            operator: if is_and { BinaryOperator::And } else { BinaryOperator::Or },
+            right_operand: Box::new(operand),
+        };
+    }
+    chain
+}
+
+impl Expression {
+    /// Folds a unary minus over a number literal into a single negative number, so `-5` becomes `Number(-5)` instead of a `UnaryOperation`
+    ///
+    /// The parser reads `-5` as a minus applied to `5`, the only exception being `-9223372036854775808`, whose magnitude has no literal of its own; this pass is for consumers that want literal constants as plain numbers. A minus over anything else, such as `-a`, is kept as it is.
+    ///
+    /// # Returns
+    /// The expression with every negated number literal folded
+    pub fn simplify_negation(self) -> Expression {
+        match self {
+            Expression::UnaryOperation { operand, operator } => match (operator, operand.simplify_negation()) {
+                (UnaryOperator::Minus, Expression::Number(n)) if n.checked_neg().is_some() => Expression::Number(-n),
+                (operator, operand) => Expression::UnaryOperation {
+                    operand: Box::new(operand),
+                    operator,
+                },
+            },
+            Expression::BinaryOperation { left_operand, operator, right_operand } => Expression::BinaryOperation {
+                left_operand: Box::new(left_operand.simplify_negation()),
+                operator,
+                right_operand: Box::new(right_operand.simplify_negation()),
+            },
+            Expression::FunctionCall { name, args } => Expression::FunctionCall {
+                name,
+                args: args.into_iter().map(Expression::simplify_negation).collect(),
+            },
+            Expression::Row(items) => Expression::Row(items.into_iter().map(Expression::simplify_negation).collect()),
+            Expression::IsNull { operand, negated } => Expression::IsNull {
+                operand: Box::new(operand.simplify_negation()),
+                negated,
+            },
+            Expression::IsUnknown { operand, negated } => Expression::IsUnknown {
+                operand: Box::new(operand.simplify_negation()),
+                negated,
+            },
+            Expression::InList { expr, list, negated } => Expression::InList {
+                expr: Box::new(expr.simplify_negation()),
+                list: list.into_iter().map(Expression::simplify_negation).collect(),
+                negated,
+            },
+            Expression::InSubquery { expr, subquery, negated } => Expression::InSubquery {
+                expr: Box::new(expr.simplify_negation()),
+                subquery,
+                negated,
+            },
+            Expression::Quantified { left, op, quantifier, subquery } => Expression::Quantified {
+                left: Box::new(left.simplify_negation()),
+                op,
+                quantifier,
+                subquery,
+            },
+            Expression::Alias { expr, alias } => Expression::Alias {
+                expr: Box::new(expr.simplify_negation()),
+                alias,
+            },
+            Expression::Like { expr, pattern, negated, escape } => Expression::Like {
+                expr: Box::new(expr.simplify_negation()),
+                pattern: Box::new(pattern.simplify_negation()),
+                negated,
+                escape,
+            },
+            Expression::DistinctFrom { left, right, negated } => Expression::DistinctFrom {
+                left: Box::new(left.simplify_negation()),
+                right: Box::new(right.simplify_negation()),
+                negated,
+            },
+            Expression::Between { expr, low, high, negated } => Expression::Between {
+                expr: Box::new(expr.simplify_negation()),
+                low: Box::new(low.simplify_negation()),
+                high: Box::new(high.simplify_negation()),
+                negated,
+            },
+            Expression::Case { operand, branches, else_result } => rewrite_case(operand, branches, else_result, Expression::simplify_negation),
+            Expression::And(operands) => Expression::And(operands.into_iter().map(Expression::simplify_negation).collect()),
+            Expression::Or(operands) => Expression::Or(operands.into_iter().map(Expression::simplify_negation).collect()),
+            other => other,
+        }
+    }
+}
+
+fn fold_binary(left: Expression, operator: BinaryOperator, right: Expression) -> Expression {
+    let folded = match (&left, &operator, &right) {
+        (Expression::Number(l), _, Expression::Number(r)) => match (u64::try_from(*l), u64::try_from(*r)) {
+            (Ok(l), Ok(r)) => fold_numbers(l, &operator, r),
+            _ => None,
+        },
+
+        // AND/OR short-circuit on a literal operand; the other operand is kept as it is
+        (Expression::Bool(false), BinaryOperator::And, _) | (_, BinaryOperator::And, Expression::Bool(false)) => {
+            Some(Expression::Bool(false))
+        }
+        (Expression::Bool(true), BinaryOperator::Or, _) | (_, BinaryOperator::Or, Expression::Bool(true)) => {
+            Some(Expression::Bool(true))
+        }
+        (Expression::Bool(true), BinaryOperator::And, _) | (Expression::Bool(false), BinaryOperator::Or, _) => {
+            return right;
+        }
+        (_, BinaryOperator::And, Expression::Bool(true)) | (_, BinaryOperator::Or, Expression::Bool(false)) => {
+            return left;
+        }
+        _ => None,
+    };
+
+    folded.unwrap_or(Expression::BinaryOperation {
+        left_operand: Box::new(left),
+        operator,
+        right_operand: Box::new(right),
+    })
+}
+
+fn fold_numbers(left: u64, operator: &BinaryOperator, right: u64) -> Option<Expression> {
+    let folded = match operator {
+        BinaryOperator::Plus => number(left.checked_add(right)?)?,
+        BinaryOperator::Minus => number(left.checked_sub(right)?)?,
+        BinaryOperator::Multiply => number(left.checked_mul(right)?)?,
+        BinaryOperator::Divide => number(left.checked_div(right)?)?,
+        BinaryOperator::Power => number(left.checked_pow(u32::try_from(right).ok()?)?)?,
+        BinaryOperator::Equal => Expression::Bool(left == right),
+        BinaryOperator::NotEqual => Expression::Bool(left != right),
+        BinaryOperator::GreaterThan => Expression::Bool(left > right),
+        BinaryOperator::GreaterThanOrEqual => Expression::Bool(left >= right),
+        BinaryOperator::LessThan => Expression::Bool(left < right),
+        BinaryOperator::LessThanOrEqual => Expression::Bool(left <= right),
+        BinaryOperator::And | BinaryOperator::Or => return None,
+    };
+    Some(folded)
+}
+
+/// Wraps a folded result in a number literal, if it fits
+fn number(value: u64) -> Option<Expression> {
+    i64::try_from(value).ok().map(Expression::Number)
+}
+
+/// Applies `rewrite` to the operand, every condition and result, and the `ELSE` result of a `CASE` expression
+fn rewrite_case(
+    operand: Option<Box<Expression>>,
+    branches: Vec<(Expression, Expression)>,
+    else_result: Option<Box<Expression>>,
+    rewrite: fn(Expression) -> Expression,
+) -> Expression {
+    Expression::Case {
+        operand: operand.map(|operand| Box::new(rewrite(*operand))),
+        branches: branches.into_iter().map(|(condition, result)| (rewrite(condition), rewrite(result))).collect(),
+        else_result: else_result.map(|else_result| Box::new(rewrite(*else_result))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{ident, num, boolean, binary, unary, call};
+    use crate::statement::Statement;
+
+    #[test]
+    fn test_fold_arithmetic() {
+        let expr = binary(num(2), BinaryOperator::Plus, binary(num(3), BinaryOperator::Multiply, num(4)));
+        assert_eq!(fold_constants(expr), num(14));
+
+        let expr = binary(num(2), BinaryOperator::Power, binary(num(3), BinaryOperator::Power, num(2)));
+        assert_eq!(fold_constants(expr), num(512));
+
+        let expr = binary(binary(num(10), BinaryOperator::Minus, num(4)), BinaryOperator::Divide, num(3));
+        assert_eq!(fold_constants(expr), num(2));
+    }
+
+    #[test]
+    fn test_fold_preserves_columns() {
+        let expr = binary(ident("A"), BinaryOperator::Plus, num(1));
+        assert_eq!(fold_constants(expr), binary(ident("A"), BinaryOperator::Plus, num(1)));
+
+        let expr = binary(ident("A"), BinaryOperator::Plus, binary(num(1), BinaryOperator::Plus, num(2)));
+        assert_eq!(fold_constants(expr), binary(ident("A"), BinaryOperator::Plus, num(3)));
+    }
+
+    #[test]
+    fn test_fold_leaves_failing_arithmetic() {
+        let expr = binary(num(1), BinaryOperator::Divide, num(0));
+        assert_eq!(fold_constants(expr), binary(num(1), BinaryOperator::Divide, num(0)));
+
+        let expr = binary(num(1), BinaryOperator::Minus, num(2));
+        assert_eq!(fold_constants(expr), binary(num(1), BinaryOperator::Minus, num(2)));
+    }
+
+    #[test]
+    fn test_fold_boolean_short_circuit() {
+        let expr = binary(boolean(false), BinaryOperator::And, ident("A"));
+        assert_eq!(fold_constants(expr), boolean(false));
+
+        let expr = binary(ident("A"), BinaryOperator::Or, boolean(true));
+        assert_eq!(fold_constants(expr), boolean(true));
+
+        let expr = binary(boolean(true), BinaryOperator::And, ident("A"));
+        assert_eq!(fold_constants(expr), ident("A"));
+
+        let expr = binary(binary(num(1), BinaryOperator::LessThan, num(2)), BinaryOperator::And, ident("A"));
+        assert_eq!(fold_constants(expr), ident("A"));
+
+        let expr = unary(UnaryOperator::Not, boolean(true));
+        assert_eq!(fold_constants(expr), boolean(false));
+    }
+
+    #[test]
+    fn test_fold_inside_function_call() {
+        let expr = call("COALESCE", vec![ident("A"), binary(num(1), BinaryOperator::Plus, num(1))]);
+        assert_eq!(fold_constants(expr), call("COALESCE", vec![ident("A"), num(2)]));
+    }
+
+    #[test]
+    fn test_fold_inside_case() {
+        let expr = Expression::Case {
+            operand: None,
+            branches: vec![(ident("A"), binary(num(2), BinaryOperator::Multiply, num(3)))],
+            else_result: Some(Box::new(binary(num(1), BinaryOperator::Plus, num(1)))),
+        };
+        assert_eq!(
+            fold_constants(expr),
+            Expression::Case { operand: None, branches: vec![(ident("A"), num(6))], else_result: Some(Box::new(num(2))) }
+        );
+    }
+
+    #[test]
+    fn test_flatten_and_chain() {
+        let expr = binary(binary(ident("A"), BinaryOperator::And, ident("B")), BinaryOperator::And, ident("C"));
+        assert_eq!(flatten_boolean(expr), Expression::And(vec![ident("A"), ident("B"), ident("C")]));
+    }
+
+    #[test]
+    fn test_flatten_keeps_and_and_or_separate() {
+        let expr = binary(
+            binary(ident("A"), BinaryOperator::Or, ident("B")),
+            BinaryOperator::Or,
+            binary(ident("C"), BinaryOperator::And, binary(ident("D"), BinaryOperator::And, ident("E"))),
+        );
+        assert_eq!(flatten_boolean(expr), Expression::Or(vec![
+            ident("A"),
+            ident("B"),
+            Expression::And(vec![ident("C"), ident("D"), ident("E")]),
+        ]));
+    }
+
+    #[test]
+    fn test_unflatten_round_trip() {
+        let expr = binary(
+            binary(ident("A"), BinaryOperator::And, unary(UnaryOperator::Not, binary(ident("B"), BinaryOperator::Or, ident("C")))),
+            BinaryOperator::And,
+            ident("D"),
+        );
+        let flattened = flatten_boolean(binary(
+            binary(ident("A"), BinaryOperator::And, unary(UnaryOperator::Not, binary(ident("B"), BinaryOperator::Or, ident("C")))),
+            BinaryOperator::And,
+            ident("D"),
+        ));
+        assert_eq!(unflatten_boolean(flattened), expr);
+    }
+
+    #[test]
+    fn test_unflatten_degenerate_nodes() {
+        assert_eq!(unflatten_boolean(Expression::And(vec![])), boolean(true));
+        assert_eq!(unflatten_boolean(Expression::Or(vec![])), boolean(false));
+        assert_eq!(unflatten_boolean(Expression::Or(vec![ident("A")])), ident("A"));
+    }
+
+    #[test]
+    fn test_simplify_negation() {
+        assert_eq!(unary(UnaryOperator::Minus, num(5)).simplify_negation(), num(-5));
+        assert_eq!(unary(UnaryOperator::Minus, ident("A")).simplify_negation(), unary(UnaryOperator::Minus, ident("A")));
+
+        let expr = binary(ident("A"), BinaryOperator::GreaterThan, unary(UnaryOperator::Minus, unary(UnaryOperator::Minus, num(5))));
+        assert_eq!(expr.simplify_negation(), binary(ident("A"), BinaryOperator::GreaterThan, num(5)));
+
+        // Arithmetic on the folded negative number is left for the database
+        let expr = binary(unary(UnaryOperator::Minus, num(2)), BinaryOperator::Plus, num(3)).simplify_negation();
+        assert_eq!(fold_constants(expr.clone()), expr);
+
+        // The printed negative number keeps its sign on the number, so the output means the same when parsed back
+        let Statement::Select { columns, .. } = crate::parse("SELECT -5 + 1, 2 * -3 FROM t;").unwrap() else {
+            panic!("Expected a SELECT");
+        };
+        for (column, expected) in columns.into_iter().zip(["((-5) + 1)", "(2 * (-3))"]) {
+            let simplified = column.simplify_negation();
+            assert_eq!(simplified.to_string(), expected);
+            let Statement::Select { columns, .. } = crate::parse(&format!("SELECT {} FROM t;", simplified)).unwrap() else {
+                panic!("Expected a SELECT");
+            };
+            assert_eq!(columns[0].clone().simplify_negation(), simplified);
+        }
+    }
+}
@@ -1,44 +1,182 @@
-use crate::statement::{Statement, Expression, TableColumn, DBType, Constraint, BinaryOperator, UnaryOperator};
-use crate::token::{Token, Keyword};
+use crate::statement::{Statement, CommentedStatement, Expression, TableColumn, DBType, Constraint, BinaryOperator, UnaryOperator, IntervalUnit, OrderDirection, Quantifier, FromSource, Associativity, GroupBy, CurrentValue, TableSample, SampleMethod, QualifiedName};
+use crate::token::{Token, Keyword, Span};
 use crate::error::Error;
+use crate::dialect::Dialect;
+
+/// Binding power of the IS predicate, the same as the equality operators
+const PREDICATE_PRECEDENCE: u8 = BinaryOperator::Equal.precedence();
+
+/// Binding power of a prefix minus or plus, tighter than every binary operator, so the sign takes only the operand right after it
+const PREFIX_PRECEDENCE: u8 = BinaryOperator::Power.precedence() + 1;
+
+/// Default limit for expression nesting, see `Parser::with_max_depth`
+const DEFAULT_MAX_DEPTH: usize = 256;
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    anonymous_parameters: u32,  // Number of `?` placeholders seen so far
+    numbered_parameters: bool,  // Whether a `?N` placeholder has been seen
+    relaxed: bool,              // Whether end of input may stand in for the semicolon
+    spans: Vec<Span>,           // Position of each token, used in error messages
+    depth: usize,               // Current expression nesting depth
+    max_depth: usize,           // Nesting depth at which parsing gives up
+    int_bounds: Option<(i64, i64)>,  // Inclusive range allowed for number literals
+    strict: bool,               // Whether a keyword in identifier position gets a reserved-word error
+    dialect: Dialect,           // Dialect the tokens were produced for
+    token_budget: Option<usize>,  // Number of tokens parsing may consume before giving up
+    consumed: usize,            // Number of tokens consumed so far
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self {
+        let mut parser = Self {
             tokens,
             current: 0,
-        }
+            anonymous_parameters: 0,
+            numbered_parameters: false,
+            relaxed: false,
+            spans: Vec::new(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            int_bounds: None,
+            strict: false,
+            dialect: Dialect::Generic,
+            token_budget: None,
+            consumed: 0,
+        };
+        parser.skip_comments();
+        parser
+    }
+
+    /// Caps how many tokens parsing may consume, so a host can bound the cost of syntactically huge input
+    pub fn with_token_budget(mut self, budget: usize) -> Self {
+        self.token_budget = Some(budget);
+        self
+    }
+
+    /// Sets the dialect the tokens were produced for, which should match the one given to `Tokenizer::new_with_dialect`
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Reports a keyword used where a table, column or index name is expected as a reserved word, instead of as an unexpected token
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Restricts number literals to the inclusive range `min..=max`, for embedders whose integers are narrower than `i64`. A minus written directly before a number is checked as part of it, so `i32::MIN..=i32::MAX` accepts `-2147483648`, even though the literal is still parsed as a minus over `2147483648`.
+    pub fn with_int_bounds(mut self, min: i64, max: i64) -> Self {
+        self.int_bounds = Some((min, max));
+        self
+    }
+
+    /// Sets how deeply expressions may nest before parsing fails, which keeps hostile input from overflowing the stack
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Attaches the token positions reported by `Tokenizer::spans`, so errors can say where they happened
+    pub fn with_spans(mut self, spans: Vec<Span>) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Parses a statement like `parse`, but accepts a missing trailing semicolon at the end of input
+    pub fn parse_relaxed(&mut self) -> Result<Statement, Error> {
+        self.relaxed = true;
+        self.parse()
     }
 
+    /// Parses a single statement, which must be the whole input. Anything left after its terminating semicolon is reported as an unexpected token, so use `parse_next` to read a script of several statements.
     pub fn parse(&mut self) -> Result<Statement, Error> {
+        let statement = self.parse_next()?;
+        if !self.is_at_end() {
+            return Err(self.unexpected_token("end of input"));
+        }
+        Ok(statement)
+    }
+
+    /// Parses a single statement like `parse`, together with the comments written before it. There are only comments to return when the tokenizer kept them, see `Tokenizer::with_keep_comments`.
+    pub fn parse_with_comments(&mut self) -> Result<CommentedStatement, Error> {
+        let leading_comments = self.leading_comments();
+        let statement = self.parse()?;
+        Ok(CommentedStatement { leading_comments, statement })
+    }
+
+    /// Returns the text of the comment tokens directly before the current token, in the order they were written
+    fn leading_comments(&self) -> Vec<String> {
+        let before = &self.tokens[..self.current.min(self.tokens.len())];
+        let start = before.iter().rposition(|token| !matches!(token, Token::Comment(_))).map_or(0, |index| index + 1);
+        before[start..]
+            .iter()
+            .filter_map(|token| match token {
+                Token::Comment(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Parses the next statement and leaves the parser after it, so calling it again reads the statement that follows
+    pub fn parse_next(&mut self) -> Result<Statement, Error> {
+        // No tokens besides comments and the end marker means nothing was submitted, which is not the same as a statement cut short
+        if self.is_at_end() {
+            return Err(Error::EmptyInput);
+        }
+
+        // Placeholders are numbered per statement when several are parsed in turn
+        self.anonymous_parameters = 0;
+        self.numbered_parameters = false;
+
+        let result = self.parse_statement();
+        if self.over_budget() {
+            return Err(Error::ParserError("token budget exceeded".to_string()));
+        }
+        result
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, Error> {
         match self.peek() {
             Some(Token::Keyword(Keyword::Select)) => self.parse_select(),
+            Some(Token::Keyword(Keyword::With)) => self.parse_with(),
+            Some(Token::Keyword(Keyword::Values)) => self.parse_values(),
+            Some(Token::Keyword(Keyword::Insert)) => self.parse_insert(),
+            Some(Token::Keyword(Keyword::Show)) => self.parse_show_tables(),
+            Some(Token::Keyword(Keyword::Set)) => self.parse_set_variable(),
+            // DESC at the start of a statement can only mean DESCRIBE, never a sort direction
+            Some(Token::Keyword(Keyword::Describe)) | Some(Token::Keyword(Keyword::Desc)) => self.parse_describe(),
+            Some(Token::Keyword(Keyword::Begin)) => self.parse_transaction_control(Statement::Begin),
+            Some(Token::Keyword(Keyword::Commit)) => self.parse_transaction_control(Statement::Commit),
+            Some(Token::Keyword(Keyword::Rollback)) => self.parse_transaction_control(Statement::Rollback),
             Some(Token::Keyword(Keyword::Create)) => {
                 self.advance();
                 match self.peek() {
                     Some(Token::Keyword(Keyword::Table)) => self.parse_create_table(),
                     Some(Token::Keyword(Keyword::Unique)) | Some(Token::Keyword(Keyword::Index)) => self.parse_create_index(),
-                    Some(token) => Err(Error::UnexpectedToken {
-                        expected: "TABLE or INDEX".to_string(),
-                        found: format!("{:?}", token),
-                    }),
+                    Some(Token::Keyword(Keyword::View)) => self.parse_create_view(),
+                    Some(_) => Err(self.unexpected_token("TABLE, INDEX or VIEW")),
                     None => Err(Error::UnexpectedEOF),
                 }
             },
-            Some(token) => Err(Error::UnexpectedToken {
-                expected: "SELECT or CREATE".to_string(),
-                found: format!("{:?}", token),
-            }),
+            Some(_) => Err(self.unexpected_token("SELECT, WITH, VALUES, INSERT, CREATE, SHOW, SET, DESCRIBE, BEGIN, COMMIT or ROLLBACK")),
             None => Err(Error::UnexpectedEOF),
         }
     }
 
     fn parse_select(&mut self) -> Result<Statement, Error> {
+        let select = self.parse_select_body()?;
+
+        // Expect semicolon
+        self.expect_statement_end()?;
+
+        Ok(select)
+    }
+
+    /// Parses a SELECT up to, but not including, whatever terminates it (a semicolon or a closing parenthesis)
+    fn parse_select_body(&mut self) -> Result<Statement, Error> {
         // Consume SELECT
         self.advance();
 
@@ -51,17 +189,14 @@ impl Parser {
         }
         self.advance();
 
-        // Parse table name
-        let from = match self.peek() {
-            Some(Token::Identifier(name)) => {
-                let name = name.clone();
-                self.advance();
-                name
-            }
-            Some(token) => return Err(Error::ParserError(format!("Expected table name, found {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
-        };
+        // Parse the table name or derived table
+        let from = self.parse_from_source()?;
+
+        self.parse_select_clauses(columns, from)
+    }
 
+    /// Parses the clauses that may follow FROM in a SELECT, from WHERE through the row limit, and builds the statement
+    fn parse_select_clauses(&mut self, columns: Vec<Expression>, from: FromSource) -> Result<Statement, Error> {
         // Parse optional WHERE clause
         let r#where = if let Some(Token::Keyword(Keyword::Where)) = self.peek() {
             self.advance();
@@ -70,6 +205,43 @@ impl Parser {
             None
         };
 
+        // Parse optional GROUP BY clause
+        let groupby = if let Some(Token::Keyword(Keyword::Group)) = self.peek() {
+            self.advance();
+            self.expect_keyword(Keyword::By)?;
+
+            let mut expressions = Vec::new();
+            loop {
+                expressions.push(self.parse_expression()?);
+
+                if let Some(Token::Comma) = self.peek() {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+
+            // WITH here can only be the ROLLUP modifier, since a CTE list never follows a clause
+            let rollup = if let Some(Token::Keyword(Keyword::With)) = self.peek() {
+                self.advance();
+                self.expect_keyword(Keyword::Rollup)?;
+                true
+            } else {
+                false
+            };
+            Some(GroupBy { expressions, rollup })
+        } else {
+            None
+        };
+
+        // Parse optional HAVING clause
+        let having = if let Some(Token::Keyword(Keyword::Having)) = self.peek() {
+            self.advance();
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
         // Parse optional ORDER BY clause
         let mut orderby = Vec::new();
         if let Some(Token::Keyword(Keyword::Order)) = self.peek() {
@@ -97,6 +269,23 @@ impl Parser {
                     }
                     _ => expr,
                 };
+
+                // Check for NULLS FIRST/LAST, which wraps the direction if there is one
+                let expr = if let Some(Token::Keyword(Keyword::Nulls)) = self.peek() {
+                    self.advance();
+                    let operator = match self.peek() {
+                        Some(Token::Keyword(Keyword::First)) => UnaryOperator::NullsFirst,
+                        Some(Token::Keyword(Keyword::Last)) => UnaryOperator::NullsLast,
+                        _ => return Err(self.unexpected_token("FIRST or LAST")),
+                    };
+                    self.advance();
+                    Expression::UnaryOperation {
+                        operand: Box::new(expr),
+                        operator,
+                    }
+                } else {
+                    expr
+                };
                 
                 orderby.push(expr);
                 
@@ -108,45 +297,331 @@ impl Parser {
             }
         }
 
-        // Expect semicolon
-        self.expect_token(Token::Semicolon)?;
+        let (limit, offset) = self.parse_limit_offset()?;
 
         Ok(Statement::Select {
             columns,
             from,
             r#where,
+            groupby,
+            having,
             orderby,
+            limit,
+            offset,
         })
     }
 
-    fn parse_create_table(&mut self) -> Result<Statement, Error> {
-        // Expect TABLE
-        self.expect_keyword(Keyword::Table)?;
+    /// Parses the optional row limit at the end of a SELECT, returning `(limit, offset)`. Accepts `LIMIT count [OFFSET skip]`, MySQL's `LIMIT skip, count`, and the standard `OFFSET skip {ROW | ROWS} FETCH NEXT count {ROW | ROWS} ONLY` where either half may be left out.
+    fn parse_limit_offset(&mut self) -> Result<(Option<u64>, Option<u64>), Error> {
+        if let Some(Token::Keyword(Keyword::Limit)) = self.peek() {
+            self.advance();
+            let first = self.parse_row_count("LIMIT")?;
 
-        // Parse table name
-        let table_name = match self.peek() {
-            Some(Token::Identifier(name)) => {
+            // MySQL's `LIMIT skip, count` puts the offset first
+            if let Some(Token::Comma) = self.peek() {
+                self.advance();
+                let limit = self.parse_row_count("LIMIT")?;
+                if let Some(Token::Keyword(Keyword::Offset)) = self.peek() {
+                    return Err(Error::ParserError("OFFSET cannot follow LIMIT with two arguments, which already gives the offset".to_string()));
+                }
+                return Ok((Some(limit), Some(first)));
+            }
+
+            let offset = if let Some(Token::Keyword(Keyword::Offset)) = self.peek() {
+                self.advance();
+                Some(self.parse_row_count("OFFSET")?)
+            } else {
+                None
+            };
+            return Ok((Some(first), offset));
+        }
+
+        let mut offset = None;
+        if let Some(Token::Keyword(Keyword::Offset)) = self.peek() {
+            self.advance();
+            offset = Some(self.parse_row_count("OFFSET")?);
+            // ROW or ROWS is optional after the offset
+            if let Some(Token::Keyword(Keyword::Row | Keyword::Rows)) = self.peek() {
+                self.advance();
+            }
+        }
+
+        let mut limit = None;
+        if let Some(Token::Keyword(Keyword::Fetch)) = self.peek() {
+            self.advance();
+            self.expect_keyword(Keyword::Next)?;
+            limit = Some(self.parse_row_count("FETCH NEXT")?);
+            match self.peek() {
+                Some(Token::Keyword(Keyword::Row | Keyword::Rows)) => self.advance(),
+                Some(_) => return Err(self.unexpected_token("ROW or ROWS")),
+                None => return Err(Error::UnexpectedEOF),
+            }
+            self.expect_keyword(Keyword::Only)?;
+        }
+
+        Ok((limit, offset))
+    }
+
+    fn parse_row_count(&mut self, clause: &str) -> Result<u64, Error> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.advance();
+                Ok(n)
+            }
+            Some(token) => Err(Error::ParserError(format!("Expected a row count after {}, found {:?}", clause, token))),
+            None => Err(Error::UnexpectedEOF),
+        }
+    }
+
+    /// Parses what follows FROM: a table name, or a parenthesized SELECT with a mandatory `AS alias`
+    fn parse_from_source(&mut self) -> Result<FromSource, Error> {
+        // ONLY is a table named ONLY unless another name follows it
+        let only = matches!(self.peek(), Some(Token::Keyword(Keyword::Only)))
+            && match self.peek_ahead(1) {
+                Some(Token::Identifier(_)) => true,
+                Some(Token::Keyword(keyword)) => !keyword.is_reserved(),
+                _ => false,
+            };
+        if only {
+            self.advance();
+        }
+        if !only && self.peek() == Some(&Token::LeftParentheses) {
+            self.advance();
+            return self.parse_derived_table();
+        }
+
+        let name = self.parse_qualified_name("table name")?;
+        let sample = if let Some(Token::Keyword(Keyword::Tablesample)) = self.peek() {
+            self.advance();
+            Some(self.parse_table_sample()?)
+        } else {
+            None
+        };
+        Ok(FromSource::Table { name, only, sample })
+    }
+
+    /// Parses the rest of a derived table, `(SELECT ...) AS alias`, after the opening parenthesis
+    fn parse_derived_table(&mut self) -> Result<FromSource, Error> {
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+            return Err(self.unexpected_token("SELECT"));
+        }
+        // Derived tables nest through here rather than through expressions, so they count against the same depth limit
+        self.depth += 1;
+        let query = if self.depth > self.max_depth {
+            Err(Error::ParserError("query nesting too deep".to_string()))
+        } else {
+            self.parse_select_body().map(Box::new)
+        };
+        self.depth -= 1;
+        let query = query?;
+        self.expect_token(Token::RightParentheses)?;
+
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::As))) {
+            return Err(Error::ParserError("A derived table must have an alias".to_string()));
+        }
+        self.advance();
+        let alias = self.expect_identifier("derived table alias")?;
+        Ok(FromSource::Derived { query, alias })
+    }
+
+    fn parse_with(&mut self) -> Result<Statement, Error> {
+        // Consume WITH
+        self.advance();
+
+        let mut ctes = Vec::new();
+        loop {
+            let name = self.expect_identifier("common table expression name")?;
+            self.expect_keyword(Keyword::As)?;
+            self.expect_token(Token::LeftParentheses)?;
+            if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+                return Err(self.unexpected_token("SELECT"));
+            }
+            let query = self.parse_select_body()?;
+            self.expect_token(Token::RightParentheses)?;
+            ctes.push((name, query));
+
+            if let Some(Token::Comma) = self.peek() {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        // The main statement follows the common table expressions
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+            return Err(self.unexpected_token("SELECT"));
+        }
+        let body = self.parse_select()?;
+
+        Ok(Statement::With {
+            ctes,
+            body: Box::new(body),
+        })
+    }
+
+    fn parse_show_tables(&mut self) -> Result<Statement, Error> {
+        // Consume SHOW
+        self.advance();
+        self.expect_keyword(Keyword::Tables)?;
+        self.expect_statement_end()?;
+        Ok(Statement::ShowTables)
+    }
+
+    fn parse_set_variable(&mut self) -> Result<Statement, Error> {
+        // Consume SET
+        self.advance();
+
+        let name = match self.peek() {
+            Some(Token::Variable(name)) => {
                 let name = name.clone();
                 self.advance();
                 name
             }
-            Some(token) => return Err(Error::ParserError(format!("Expected table name, found {:?}", token))),
+            Some(token) => return Err(Error::ParserError(format!("Expected variable after SET, found {:?}", token))),
             None => return Err(Error::UnexpectedEOF),
         };
+        self.expect_token(Token::Equal)?;
+        let value = self.parse_expression()?;
+
+        self.expect_statement_end()?;
+        Ok(Statement::SetVariable { name, value })
+    }
+
+    fn parse_describe(&mut self) -> Result<Statement, Error> {
+        // Consume DESCRIBE or DESC
+        self.advance();
+
+        let table_name = self.parse_qualified_name("table name")?;
+
+        self.expect_statement_end()?;
+        Ok(Statement::Describe { table_name })
+    }
+
+    fn parse_values(&mut self) -> Result<Statement, Error> {
+        // Consume VALUES
+        self.advance();
+        let rows = self.parse_values_rows()?;
+        check_row_arity(&rows, rows[0].len())?;
+        self.expect_statement_end()?;
+        Ok(Statement::Values(rows))
+    }
+
+    fn parse_insert(&mut self) -> Result<Statement, Error> {
+        // Consume INSERT
+        self.advance();
+        self.expect_keyword(Keyword::Into)?;
+
+        let table_name = self.parse_qualified_name("table name")?;
+
+        // Parse the optional column list
+        let mut columns = Vec::new();
+        if let Some(Token::LeftParentheses) = self.peek() {
+            self.advance();
+            loop {
+                columns.push(self.expect_identifier("column name")?);
+
+                match self.peek() {
+                    Some(Token::Comma) => self.advance(),
+                    Some(Token::RightParentheses) => {
+                        self.advance();
+                        break;
+                    }
+                    Some(_) => return Err(self.unexpected_token("comma or ')'")),
+                    None => return Err(Error::UnexpectedEOF),
+                }
+            }
+        }
+
+        self.expect_keyword(Keyword::Values)?;
+        let rows = self.parse_values_rows()?;
+        // Without a column list the first row decides how many values each row has
+        let expected = if columns.is_empty() { rows[0].len() } else { columns.len() };
+        check_row_arity(&rows, expected)?;
+
+        self.expect_statement_end()?;
+        Ok(Statement::Insert { table_name, columns, rows })
+    }
+
+    fn parse_transaction_control(&mut self, statement: Statement) -> Result<Statement, Error> {
+        // Consume BEGIN, COMMIT or ROLLBACK and the optional TRANSACTION
+        self.advance();
+        if let Some(Token::Keyword(Keyword::Transaction)) = self.peek() {
+            self.advance();
+        }
+
+        self.expect_statement_end()?;
+        Ok(statement)
+    }
+
+    fn parse_create_table(&mut self) -> Result<Statement, Error> {
+        // Expect TABLE
+        self.expect_keyword(Keyword::Table)?;
+
+        // Parse table name
+        let table_name = self.parse_qualified_name("table name")?;
+
+        // CREATE TABLE ... AS SELECT takes its columns from the query instead of a column list
+        if matches!(self.peek(), Some(Token::Keyword(Keyword::As))) {
+            self.advance();
+            if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+                return Err(self.unexpected_token("SELECT"));
+            }
+            let query = self.parse_select_body()?;
+            self.expect_statement_end()?;
+            return Ok(Statement::CreateTableAs {
+                table_name,
+                query: Box::new(query),
+            });
+        }
 
         // Expect left parenthesis
         self.expect_token(Token::LeftParentheses)?;
 
         // Parse column definitions and table constraints
         let mut column_list: Vec<TableColumn> = Vec::new();
+        let mut constraints: Vec<Constraint> = Vec::new();
         loop {
             if let Some(Token::RightParentheses) = self.peek() {
                 self.advance();
                 break;
             }
 
-            if !column_list.is_empty() {
+            if !column_list.is_empty() || !constraints.is_empty() {
                 self.expect_token(Token::Comma)?;
+                if let Some(Token::RightParentheses) = self.peek() {
+                    return Err(Error::ParserError("trailing comma before ')'".to_string()));
+                }
+            }
+
+            // Check for a named table constraint
+            let mut constraint_name = if let Some(Token::Keyword(Keyword::Constraint)) = self.peek() {
+                self.advance();
+                let name = self.expect_identifier("constraint name")?;
+                if !matches!(self.peek(), Some(Token::Keyword(Keyword::Foreign)) | Some(Token::Keyword(Keyword::Check))) {
+                    return Err(Error::ParserError(format!("Expected FOREIGN KEY or CHECK after CONSTRAINT {}", name)));
+                }
+                Some(name)
+            } else {
+                None
+            };
+
+            // Check if it's a table-level CHECK constraint
+            if let Some(Token::Keyword(Keyword::Check)) = self.peek() {
+                self.advance();
+                self.expect_token(Token::LeftParentheses)?;
+                let expr = self.parse_expression()?;
+                self.expect_token(Token::RightParentheses)?;
+
+                let constraint = Constraint::Check(expr);
+                constraints.push(match constraint_name {
+                    Some(name) => Constraint::Named {
+                        name,
+                        constraint: Box::new(constraint),
+                    },
+                    None => constraint,
+                });
+                continue;
             }
 
             // Check if it's a FOREIGN KEY constraint
@@ -156,49 +631,36 @@ impl Parser {
                 
                 // Parse (column)
                 self.expect_token(Token::LeftParentheses)?;
-                let column = match self.peek() {
-                    Some(Token::Identifier(name)) => {
-                        let name = name.clone();
-                        self.advance();
-                        name
-                    }
-                    Some(token) => return Err(Error::InvalidForeignKey(format!("Expected column name, found {:?}", token))),
-                    None => return Err(Error::InvalidForeignKey("Missing column name".to_string())),
-                };
+                let column = self.expect_identifier("column name").map_err(|error| invalid_foreign_key(error, "Missing column name"))?;
                 self.expect_token(Token::RightParentheses)?;
 
                 // Parse REFERENCES table(column)
                 self.expect_keyword(Keyword::References)?;
-                let referenced_table = match self.peek() {
-                    Some(Token::Identifier(name)) => {
-                        let name = name.clone();
-                        self.advance();
-                        name
-                    }
-                    Some(token) => return Err(Error::InvalidForeignKey(format!("Expected table name, found {:?}", token))),
-                    None => return Err(Error::InvalidForeignKey("Missing referenced table name".to_string())),
-                };
+                let referenced_table = self
+                    .parse_qualified_name("table name")
+                    .map_err(|error| invalid_foreign_key(error, "Missing referenced table name"))?;
 
                 self.expect_token(Token::LeftParentheses)?;
-                let referenced_column = match self.peek() {
-                    Some(Token::Identifier(name)) => {
-                        let name = name.clone();
-                        self.advance();
-                        name
-                    }
-                    Some(token) => return Err(Error::InvalidForeignKey(format!("Expected column name, found {:?}", token))),
-                    None => return Err(Error::InvalidForeignKey("Missing referenced column name".to_string())),
-                };
+                let referenced_column = self
+                    .expect_identifier("column name")
+                    .map_err(|error| invalid_foreign_key(error, "Missing referenced column name"))?;
                 self.expect_token(Token::RightParentheses)?;
 
                 // Find the column and add the foreign key constraint
                 let mut found = false;
                 for col in &mut column_list {
                     if col.column_name == column {
-                        col.constraints.push(Constraint::ForeignKey {
+                        let constraint = Constraint::ForeignKey {
                             column: column.clone(),
                             referenced_table: referenced_table.clone(),
                             referenced_column: referenced_column.clone(),
+                        };
+                        col.constraints.push(match constraint_name.take() {
+                            Some(name) => Constraint::Named {
+                                name,
+                                constraint: Box::new(constraint),
+                            },
+                            None => constraint,
                         });
                         found = true;
                         break;
@@ -213,12 +675,67 @@ impl Parser {
             column_list.push(self.parse_column_definition()?);
         }
 
+        validate_foreign_keys(&table_name, &column_list)?;
+
         // Expect semicolon
-        self.expect_token(Token::Semicolon)?;
+        self.expect_statement_end()?;
 
         Ok(Statement::CreateTable {
             table_name,
             column_list,
+            constraints,
+        })
+    }
+
+    fn parse_create_view(&mut self) -> Result<Statement, Error> {
+        // Consume VIEW
+        self.advance();
+
+        let view_name = self.parse_qualified_name("view name")?;
+
+        // Parse the optional list of names for the view's columns
+        let mut columns = Vec::new();
+        if let Some(Token::LeftParentheses) = self.peek() {
+            self.advance();
+            loop {
+                columns.push(self.expect_identifier("column name")?);
+
+                match self.peek() {
+                    Some(Token::Comma) => self.advance(),
+                    Some(Token::RightParentheses) => {
+                        self.advance();
+                        break;
+                    }
+                    Some(_) => return Err(self.unexpected_token("comma or ')'")),
+                    None => return Err(Error::UnexpectedEOF),
+                }
+            }
+        }
+
+        self.expect_keyword(Keyword::As)?;
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+            return Err(self.unexpected_token("SELECT"));
+        }
+        let query = self.parse_select_body()?;
+
+        // The names can only be checked against an explicit select list, not against `*`
+        if let Statement::Select { columns: select_list, .. } = &query {
+            let is_wildcard = matches!(select_list.as_slice(), [Expression::Identifier(name)] if name == "*");
+            if !columns.is_empty() && !is_wildcard && columns.len() != select_list.len() {
+                return Err(Error::ParserError(format!(
+                    "View {} names {} columns, but its query selects {}",
+                    view_name,
+                    columns.len(),
+                    select_list.len()
+                )));
+            }
+        }
+
+        self.expect_statement_end()?;
+        Ok(Statement::CreateView {
+            view_name,
+            columns,
+            query: Box::new(query),
         })
     }
 
@@ -234,74 +751,240 @@ impl Parser {
         self.expect_keyword(Keyword::Index)?;
 
         // Parse index name
-        let index_name = match self.peek() {
-            Some(Token::Identifier(name)) => {
-                let name = name.clone();
-                self.advance();
-                name
-            }
-            Some(token) => return Err(Error::ParserError(format!("Expected index name, found {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
-        };
+        let index_name = self.expect_identifier("index name")?;
 
         // Expect ON
         self.expect_keyword(Keyword::On)?;
 
         // Parse table name
-        let table_name = match self.peek() {
-            Some(Token::Identifier(name)) => {
-                let name = name.clone();
-                self.advance();
-                name
-            }
-            Some(token) => return Err(Error::ParserError(format!("Expected table name, found {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
-        };
+        let table_name = self.parse_qualified_name("table name")?;
 
-        // Parse (column_name)
+        // Parse (column_name, ...)
         self.expect_token(Token::LeftParentheses)?;
-        let column_name = match self.peek() {
-            Some(Token::Identifier(name)) => {
-                let name = name.clone();
-                self.advance();
-                name
+        if let Some(Token::RightParentheses) = self.peek() {
+            return Err(Error::ParserError("Index must have at least one column".to_string()));
+        }
+        let mut columns = Vec::new();
+        loop {
+            let column_name = self.expect_identifier("column name")?;
+            let direction = match self.peek() {
+                Some(Token::Keyword(Keyword::Asc)) => {
+                    self.advance();
+                    OrderDirection::Asc
+                }
+                Some(Token::Keyword(Keyword::Desc)) => {
+                    self.advance();
+                    OrderDirection::Desc
+                }
+                _ => OrderDirection::Asc,
+            };
+            columns.push((column_name, direction));
+
+            match self.peek() {
+                Some(Token::Comma) => self.advance(),
+                Some(Token::RightParentheses) => {
+                    self.advance();
+                    break;
+                }
+                Some(_) => return Err(self.unexpected_token("comma or ')'")),
+                None => return Err(Error::UnexpectedEOF),
             }
-            Some(token) => return Err(Error::ParserError(format!("Expected column name, found {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
+        }
+
+        // Parse optional WHERE clause of a partial index
+        let predicate = if let Some(Token::Keyword(Keyword::Where)) = self.peek() {
+            self.advance();
+            Some(self.parse_expression()?)
+        } else {
+            None
         };
-        self.expect_token(Token::RightParentheses)?;
-        self.expect_token(Token::Semicolon)?;
+
+        self.expect_statement_end()?;
 
         Ok(Statement::CreateIndex {
             is_unique,
             index_name,
             table_name,
-            column_name,
+            columns,
+            predicate,
         })
     }
 
     fn parse_column_definition(&mut self) -> Result<TableColumn, Error> {
         // Parse column name
-        let column_name = match self.peek() {
-            Some(Token::Identifier(name)) => {
-                let name = name.clone();
+        let column_name = self.expect_identifier("column name")?;
+
+        // Parse column type, where SERIAL is shorthand for an auto-incrementing INT
+        let mut constraints = Vec::new();
+        let column_type = if let Some(Token::Keyword(Keyword::Serial)) = self.peek() {
+            self.advance();
+            constraints.push(Constraint::AutoIncrement);
+            DBType::Int
+        } else {
+            self.parse_type()?
+        };
+
+        // Parse constraints
+        while let Some(constraint) = self.parse_column_constraint()? {
+            constraints.push(constraint);
+        }
+        check_column_constraints(&column_name, &constraints)?;
+
+        Ok(TableColumn {
+            column_name,
+            column_type,
+            constraints,
+        })
+    }
+
+    fn parse_column_constraint(&mut self) -> Result<Option<Constraint>, Error> {
+        let constraint = match self.peek() {
+            Some(Token::Keyword(Keyword::Primary)) => {
                 self.advance();
-                name
+                self.expect_keyword(Keyword::Key)?;
+                Constraint::PrimaryKey
             }
-            Some(token) => return Err(Error::ParserError(format!("Expected column name, found {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
+            Some(Token::Keyword(Keyword::Unique)) => {
+                self.advance();
+                Constraint::Unique
+            }
+            Some(Token::Keyword(Keyword::Null)) => {
+                self.advance();
+                Constraint::Null
+            }
+            Some(Token::Keyword(Keyword::Foreign)) => {
+                self.advance();
+                self.expect_keyword(Keyword::Key)?;
+                
+                // Parse (column)
+                self.expect_token(Token::LeftParentheses)?;
+                let column = self.expect_identifier("column name").map_err(|error| invalid_foreign_key(error, "Missing column name"))?;
+                self.expect_token(Token::RightParentheses)?;
+
+                // Parse REFERENCES table(column)
+                self.expect_keyword(Keyword::References)?;
+                let referenced_table = self
+                    .parse_qualified_name("table name")
+                    .map_err(|error| invalid_foreign_key(error, "Missing referenced table name"))?;
+
+                self.expect_token(Token::LeftParentheses)?;
+                let referenced_column = self
+                    .expect_identifier("column name")
+                    .map_err(|error| invalid_foreign_key(error, "Missing referenced column name"))?;
+                self.expect_token(Token::RightParentheses)?;
+
+                Constraint::ForeignKey {
+                    column,
+                    referenced_table,
+                    referenced_column,
+                }
+            }
+            Some(Token::Keyword(Keyword::Not)) => {
+                self.advance();
+                // NOT only starts a constraint as NOT NULL, never a boolean expression like NOT TRUE
+                match self.peek() {
+                    Some(Token::Keyword(Keyword::Null)) => self.advance(),
+                    Some(token) => {
+                        return Err(Error::ParserError(format!("Expected NULL after NOT in a column definition, found {:?}", token)));
+                    }
+                    None => return Err(Error::UnexpectedEOF),
+                }
+                Constraint::NotNull
+            }
+            Some(Token::Keyword(Keyword::AutoIncrement)) => {
+                self.advance();
+                Constraint::AutoIncrement
+            }
+            Some(Token::Keyword(Keyword::Check)) => {
+                self.advance();
+                match self.peek() {
+                    Some(Token::LeftParentheses) => {
+                        self.advance();
+                        let expr = self.parse_expression()?;
+                        self.expect_token(Token::RightParentheses)?;
+                        Constraint::Check(expr)
+                    }
+                    _ => return Err(Error::ParserError("Expected '(' after CHECK".to_string())),
+                }
+            }
+            Some(Token::Keyword(Keyword::Default)) => {
+                self.advance();
+                Constraint::Default(self.parse_expression()?)
+            }
+            Some(Token::Keyword(Keyword::Generated)) => {
+                self.advance();
+                self.expect_keyword(Keyword::Always)?;
+                self.expect_keyword(Keyword::As)?;
+                self.expect_token(Token::LeftParentheses)?;
+                let expr = self.parse_expression()?;
+                self.expect_token(Token::RightParentheses)?;
+
+                // A generated column is virtual unless STORED is given
+                let stored = match self.peek() {
+                    Some(Token::Keyword(Keyword::Stored)) => {
+                        self.advance();
+                        true
+                    }
+                    Some(Token::Keyword(Keyword::Virtual)) => {
+                        self.advance();
+                        false
+                    }
+                    _ => false,
+                };
+                Constraint::Generated { expr, stored }
+            }
+            Some(Token::Keyword(Keyword::Collate)) => {
+                self.advance();
+                match self.peek() {
+                    Some(Token::Identifier(collation)) | Some(Token::String(collation)) => {
+                        let collation = collation.clone();
+                        self.advance();
+                        Constraint::Collate(collation)
+                    }
+                    Some(token) => return Err(Error::ParserError(format!("Expected collation name, found {:?}", token))),
+                    None => return Err(Error::UnexpectedEOF),
+                }
+            }
+            Some(Token::Keyword(Keyword::Constraint)) => {
+                self.advance();
+                let name = self.expect_identifier("constraint name")?;
+                match self.parse_column_constraint()? {
+                    Some(Constraint::Named { .. }) | None => {
+                        return Err(Error::ParserError(format!("Expected constraint after CONSTRAINT {}", name)));
+                    }
+                    Some(constraint) => Constraint::Named {
+                        name,
+                        constraint: Box::new(constraint),
+                    },
+                }
+            }
+            _ => return Ok(None),
         };
+        Ok(Some(constraint))
+    }
 
-        // Parse column type
+    fn parse_type(&mut self) -> Result<DBType, Error> {
         let column_type = match self.peek() {
             Some(Token::Keyword(Keyword::Int)) => {
                 self.advance();
                 DBType::Int
             }
+            Some(Token::Keyword(Keyword::SmallInt)) => {
+                self.advance();
+                DBType::SmallInt
+            }
+            Some(Token::Keyword(Keyword::BigInt)) => {
+                self.advance();
+                DBType::BigInt
+            }
             Some(Token::Keyword(Keyword::Bool)) => {
                 self.advance();
                 DBType::Bool
             }
+            Some(Token::Keyword(Keyword::Timestamp)) => {
+                self.advance();
+                DBType::Timestamp
+            }
             Some(Token::Keyword(Keyword::Varchar)) => {
                 self.advance();
                 match self.peek() {
@@ -309,7 +992,7 @@ impl Parser {
                         self.advance();
                         let size = match self.peek() {
                             Some(Token::Number(n)) => {
-                                let value = n.clone();
+                                let value = *n;
                                 self.advance();
                                 value as usize
                             }
@@ -325,89 +1008,7 @@ impl Parser {
             Some(token) => return Err(Error::ParserError(format!("Expected type, found {:?}", token))),
             None => return Err(Error::UnexpectedEOF),
         };
-
-        // Parse constraints
-        let mut constraints = Vec::new();
-        loop {
-            match self.peek() {
-                Some(Token::Keyword(Keyword::Primary)) => {
-                    self.advance();
-                    self.expect_keyword(Keyword::Key)?;
-                    constraints.push(Constraint::PrimaryKey);
-                }
-                Some(Token::Keyword(Keyword::Foreign)) => {
-                    self.advance();
-                    self.expect_keyword(Keyword::Key)?;
-                    
-                    // Parse (column)
-                    self.expect_token(Token::LeftParentheses)?;
-                    let column = match self.peek() {
-                        Some(Token::Identifier(name)) => {
-                            let name = name.clone();
-                            self.advance();
-                            name
-                        }
-                        Some(token) => return Err(Error::InvalidForeignKey(format!("Expected column name, found {:?}", token))),
-                        None => return Err(Error::InvalidForeignKey("Missing column name".to_string())),
-                    };
-                    self.expect_token(Token::RightParentheses)?;
-
-                    // Parse REFERENCES table(column)
-                    self.expect_keyword(Keyword::References)?;
-                    let referenced_table = match self.peek() {
-                        Some(Token::Identifier(name)) => {
-                            let name = name.clone();
-                            self.advance();
-                            name
-                        }
-                        Some(token) => return Err(Error::InvalidForeignKey(format!("Expected table name, found {:?}", token))),
-                        None => return Err(Error::InvalidForeignKey("Missing referenced table name".to_string())),
-                    };
-
-                    self.expect_token(Token::LeftParentheses)?;
-                    let referenced_column = match self.peek() {
-                        Some(Token::Identifier(name)) => {
-                            let name = name.clone();
-                            self.advance();
-                            name
-                        }
-                        Some(token) => return Err(Error::InvalidForeignKey(format!("Expected column name, found {:?}", token))),
-                        None => return Err(Error::InvalidForeignKey("Missing referenced column name".to_string())),
-                    };
-                    self.expect_token(Token::RightParentheses)?;
-
-                    constraints.push(Constraint::ForeignKey {
-                        column,
-                        referenced_table,
-                        referenced_column,
-                    });
-                }
-                Some(Token::Keyword(Keyword::Not)) => {
-                    self.advance();
-                    self.expect_keyword(Keyword::Null)?;
-                    constraints.push(Constraint::NotNull);
-                }
-                Some(Token::Keyword(Keyword::Check)) => {
-                    self.advance();
-                    match self.peek() {
-                        Some(Token::LeftParentheses) => {
-                            self.advance();
-                            let expr = self.parse_expression()?;
-                            self.expect_token(Token::RightParentheses)?;
-                            constraints.push(Constraint::Check(expr));
-                        }
-                        _ => return Err(Error::ParserError("Expected '(' after CHECK".to_string())),
-                    }
-                }
-                _ => break,
-            }
-        }
-
-        Ok(TableColumn {
-            column_name,
-            column_type,
-            constraints,
-        })
+        Ok(column_type)
     }
 
     fn parse_expressions_list(&mut self) -> Result<Vec<Expression>, Error> {
@@ -420,31 +1021,38 @@ impl Parser {
             
             match self.peek() {
                 Some(Token::Keyword(Keyword::From)) => return Ok(expressions),
-                Some(token) => return Err(Error::UnexpectedToken {
-                    expected: "FROM".to_string(),
-                    found: format!("{:?}", token),
-                }),
+                Some(_) => return Err(self.unexpected_token("FROM")),
                 None => return Err(Error::UnexpectedEOF),
             }
         }
 
         // Normal expression list parsing
         loop {
-            // Parse an expression
+            // Parse an expression and its optional AS alias
             let expr = self.parse_expression()?;
+            let expr = if let Some(Token::Keyword(Keyword::As)) = self.peek() {
+                self.advance();
+                let alias = self.expect_identifier("column alias")?;
+                Expression::Alias {
+                    expr: Box::new(expr),
+                    alias,
+                }
+            } else {
+                expr
+            };
             expressions.push(expr);
 
             match self.peek() {
                 Some(Token::Comma) => {
                     self.advance();
+                    if let Some(Token::Keyword(Keyword::From)) = self.peek() {
+                        return Err(Error::ParserError("trailing comma before FROM".to_string()));
+                    }
                     continue;
                 }
                 Some(Token::Keyword(Keyword::From)) => break,
                 Some(Token::Semicolon) => break,
-                Some(token) => return Err(Error::UnexpectedToken {
-                    expected: "comma or FROM".to_string(),
-                    found: format!("{:?}", token),
-                }),
+                Some(_) => return Err(self.unexpected_token("comma or FROM")),
                 None => return Err(Error::UnexpectedEOF),
             }
         }
@@ -457,22 +1065,56 @@ impl Parser {
     }
 
     fn parse_binary_expression(&mut self, min_precedence: u8) -> Result<Expression, Error> {
-        let mut left = self.parse_prefix_expression()?;
+        // Every level of nesting passes through here, so this is where the recursion is bounded
+        self.depth += 1;
+        let result = if self.depth > self.max_depth {
+            Err(Error::ParserError("expression nesting too deep".to_string()))
+        } else {
+            self.parse_prefix_expression().and_then(|left| self.parse_binary_expression_rest(left, min_precedence))
+        };
+        self.depth -= 1;
+        result
+    }
 
+    /// Applies the operators and predicates that follow an already parsed left operand, as long as they bind at least as tightly as `min_precedence`
+    fn parse_binary_expression_rest(&mut self, mut left: Expression, min_precedence: u8) -> Result<Expression, Error> {
         while let Some(token) = self.peek() {
-            if token == &Token::Semicolon || token == &Token::Comma || 
-               token == &Token::Keyword(Keyword::From) || token == &Token::RightParentheses ||
-               token == &Token::Keyword(Keyword::Order) || token == &Token::Keyword(Keyword::Asc) ||
-               token == &Token::Keyword(Keyword::Desc) {
-                break;
+            // IS [NOT] NULL and [NOT] IN, LIKE or BETWEEN bind like a comparison operator
+            if token == &Token::Keyword(Keyword::Is) {
+                if PREDICATE_PRECEDENCE < min_precedence {
+                    break;
+                }
+                left = self.parse_is_predicate(left)?;
+                continue;
+            }
+            if self.at_negatable_predicate() {
+                if PREDICATE_PRECEDENCE < min_precedence {
+                    break;
+                }
+                left = self.parse_negatable_predicate(left)?;
+                continue;
             }
-            let precedence = self.get_binary_precedence(token);
+
+            // Anything that is not a binary operator ends the expression
+            let precedence = match self.get_binary_precedence(token) {
+                Some(precedence) => precedence,
+                None => break,
+            };
             if precedence < min_precedence {
                 break;
             }
 
             let operator = self.parse_binary_operator()?;
-            let right = self.parse_binary_expression(precedence + 1)?;
+            if let Some(Token::Keyword(Keyword::Any | Keyword::All)) = self.peek() {
+                left = self.parse_quantified_comparison(left, operator)?;
+                continue;
+            }
+            // A left-associative operator only takes tighter operators into its right operand, a right-associative one also takes itself
+            let right_precedence = match operator.associativity() {
+                Associativity::Left => precedence + 1,
+                Associativity::Right => precedence,
+            };
+            let right = self.parse_binary_expression(right_precedence)?;
 
             left = Expression::BinaryOperation {
                 left_operand: Box::new(left),
@@ -484,259 +1126,2616 @@ impl Parser {
         Ok(left)
     }
 
-    fn parse_prefix_expression(&mut self) -> Result<Expression, Error> {
-        match self.peek() {
-            Some(Token::Number(n)) => {
-                let n = *n;
-                self.advance();
-                Ok(Expression::Number(n))
-            }
-            Some(Token::String(s)) => {
-                let s = s.clone();
-                self.advance();
-                Ok(Expression::String(s))
-            }
-            Some(Token::Identifier(i)) => {
-                let i = i.clone();
-                self.advance();
-                Ok(Expression::Identifier(i))
-            }
-            Some(Token::Keyword(Keyword::True)) => {
-                self.advance();
-                Ok(Expression::Bool(true))
-            }
-            Some(Token::Keyword(Keyword::False)) => {
-                self.advance();
-                Ok(Expression::Bool(false))
-            }
-            Some(Token::LeftParentheses) => {
-                self.advance();
-                let expr = self.parse_expression()?;
-                self.expect_token(Token::RightParentheses)?;
-                Ok(expr)
-            }
-            Some(Token::Minus) => {
-                self.advance();
-                let expr = self.parse_expression()?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Minus,
-                })
-            }
-            Some(Token::Plus) => {
-                self.advance();
-                let expr = self.parse_expression()?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Plus,
-                })
+    fn parse_is_predicate(&mut self, operand: Expression) -> Result<Expression, Error> {
+        let negated = matches!(self.peek_ahead(1), Some(Token::Keyword(Keyword::Not)));
+        let target = if negated { 2 } else { 1 };
+        let is_unknown = match self.peek_ahead(target) {
+            Some(Token::Keyword(Keyword::Null)) => false,
+            Some(Token::Keyword(Keyword::Unknown)) => true,
+            Some(Token::Keyword(Keyword::Distinct)) => {
+                // Consume IS, the optional NOT and DISTINCT; the right operand binds like the right side of a comparison
+                for _ in 0..=target {
+                    self.advance();
+                }
+                self.expect_keyword(Keyword::From)?;
+                let right = self.parse_binary_expression(PREDICATE_PRECEDENCE + 1)?;
+                return Ok(Expression::DistinctFrom {
+                    left: Box::new(operand),
+                    right: Box::new(right),
+                    negated,
+                });
             }
-            Some(Token::Keyword(Keyword::Not)) => {
-                self.advance();
-                let expr = self.parse_expression()?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Not,
-                })
+            _ => {
+                for _ in 0..target {
+                    self.advance();
+                }
+                return Err(self.unexpected_token(if negated { "NULL, UNKNOWN or DISTINCT" } else { "NULL, UNKNOWN, DISTINCT or NOT" }));
             }
-            Some(token) => Err(Error::ParserError(format!("Unexpected token in prefix position: {:?}", token))),
-            None => Err(Error::UnexpectedEOF),
+        };
+
+        // Consume IS, the optional NOT and NULL or UNKNOWN
+        for _ in 0..=target {
+            self.advance();
+        }
+        let operand = Box::new(operand);
+        if is_unknown {
+            Ok(Expression::IsUnknown { operand, negated })
+        } else {
+            Ok(Expression::IsNull { operand, negated })
         }
     }
 
-    fn parse_binary_operator(&mut self) -> Result<BinaryOperator, Error> {
-        let op = match self.peek() {
-            Some(Token::Plus) => BinaryOperator::Plus,
-            Some(Token::Minus) => BinaryOperator::Minus,
-            Some(Token::Star) => BinaryOperator::Multiply,
-            Some(Token::Divide) => BinaryOperator::Divide,
-            Some(Token::Equal) => BinaryOperator::Equal,
-            Some(Token::NotEqual) => BinaryOperator::NotEqual,
-            Some(Token::GreaterThan) => BinaryOperator::GreaterThan,
-            Some(Token::GreaterThanOrEqual) => BinaryOperator::GreaterThanOrEqual,
-            Some(Token::LessThan) => BinaryOperator::LessThan,
-            Some(Token::LessThanOrEqual) => BinaryOperator::LessThanOrEqual,
-            Some(Token::Keyword(Keyword::And)) => BinaryOperator::And,
-            Some(Token::Keyword(Keyword::Or)) => BinaryOperator::Or,
-            Some(token) => return Err(Error::ParserError(format!("Unexpected token in operator position: {:?}", token))),
-            None => return Err(Error::UnexpectedEOF),
+    /// Returns whether the next tokens start `IN`, `LIKE` or `BETWEEN`, with or without a `NOT` in front. A `NOT` followed by anything else is not a predicate here and is left to prefix parsing.
+    fn at_negatable_predicate(&self) -> bool {
+        let keyword = match self.peek() {
+            Some(Token::Keyword(Keyword::Not)) => self.peek_ahead(1),
+            token => token,
         };
-        self.advance();
-        Ok(op)
+        matches!(keyword, Some(Token::Keyword(Keyword::In | Keyword::Like | Keyword::Between)))
     }
 
-    fn get_binary_precedence(&self, token: &Token) -> u8 {
-        match token {
-            Token::Keyword(Keyword::Or) => 1,
-            Token::Keyword(Keyword::And) => 2,
-            Token::Equal | Token::NotEqual => 3,
-            Token::GreaterThan | Token::GreaterThanOrEqual |
-            Token::LessThan | Token::LessThanOrEqual => 4,
-            Token::Plus | Token::Minus => 5,
-            Token::Star | Token::Divide => 6,
-            Token::Semicolon => 0,  // Semicolon has lowest precedence
-            _ => 0,
+    /// Parses `[NOT] IN`, `[NOT] LIKE` or `[NOT] BETWEEN` after its left operand, consuming the optional `NOT` once for all three
+    fn parse_negatable_predicate(&mut self, operand: Expression) -> Result<Expression, Error> {
+        let negated = matches!(self.peek(), Some(Token::Keyword(Keyword::Not)));
+        if negated {
+            self.advance();
         }
-    }
 
-    fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.current)
+        let keyword = self.peek().cloned();
+        self.advance();
+        match keyword {
+            Some(Token::Keyword(Keyword::In)) => self.parse_in_predicate(operand, negated),
+            Some(Token::Keyword(Keyword::Like)) => {
+                let pattern = self.parse_binary_expression(PREDICATE_PRECEDENCE + 1)?;
+                let escape = if let Some(Token::Keyword(Keyword::Escape)) = self.peek() {
+                    self.advance();
+                    Some(self.parse_escape_character()?)
+                } else {
+                    None
+                };
+                Ok(Expression::Like {
+                    expr: Box::new(operand),
+                    pattern: Box::new(pattern),
+                    negated,
+                    escape,
+                })
+            }
+            _ => {
+                // The bounds bind tighter than AND, so the AND between them ends the lower bound
+                let low = self.parse_binary_expression(PREDICATE_PRECEDENCE + 1)?;
+                self.expect_keyword(Keyword::And)?;
+                let high = self.parse_binary_expression(PREDICATE_PRECEDENCE + 1)?;
+                Ok(Expression::Between {
+                    expr: Box::new(operand),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                })
+            }
+        }
     }
 
-    fn advance(&mut self) {
-        self.current += 1;
-    }
+    /// Parses the parenthesized part of `[NOT] IN (...)`, after IN has been consumed. A parenthesis followed by SELECT is a subquery, anything else is a list of values.
+    fn parse_in_predicate(&mut self, operand: Expression, negated: bool) -> Result<Expression, Error> {
+        self.expect_token(Token::LeftParentheses)?;
 
-    fn expect_token(&mut self, expected: Token) -> Result<(), Error> {
-        match self.peek() {
-            Some(token) if token == &expected => {
-                self.advance();
-                Ok(())
-            }
-            Some(token) => Err(Error::UnexpectedToken {
-                expected: format!("{:?}", expected),
-                found: format!("{:?}", token),
-            }),
-            None => Err(Error::UnexpectedEOF),
+        if let Some(Token::Keyword(Keyword::Select)) = self.peek() {
+            let subquery = self.parse_select_body()?;
+            self.expect_token(Token::RightParentheses)?;
+            return Ok(Expression::InSubquery {
+                expr: Box::new(operand),
+                subquery: Box::new(subquery),
+                negated,
+            });
         }
+
+        let list = self.parse_expression_tuple_rest()?;
+        Ok(Expression::InList {
+            expr: Box::new(operand),
+            list,
+            negated,
+        })
     }
 
-    fn expect_keyword(&mut self, expected: Keyword) -> Result<(), Error> {
-        match self.peek() {
-            Some(Token::Keyword(keyword)) if keyword == &expected => {
-                self.advance();
-                Ok(())
-            }
-            Some(token) => Err(Error::UnexpectedToken {
-                expected: format!("{:?}", expected),
+    /// Parses a non-empty, comma-separated list of expressions and the closing parenthesis after it. The opening parenthesis has already been consumed.
+    fn parse_expression_tuple_rest(&mut self) -> Result<Vec<Expression>, Error> {
+        let mut list = Vec::new();
+        loop {
+            list.push(self.parse_expression()?);
+            match self.peek() {
+                Some(Token::Comma) => {
+                    self.advance();
+                    if let Some(Token::RightParentheses) = self.peek() {
+                        return Err(Error::ParserError("trailing comma before ')'".to_string()));
+                    }
+                }
+                Some(Token::RightParentheses) => {
+                    self.advance();
+                    break;
+                }
+                Some(_) => return Err(self.unexpected_token("comma or ')'")),
+                None => return Err(Error::UnexpectedEOF),
+            }
+        }
+        Ok(list)
+    }
+
+    /// Parses the rows of a VALUES list, `(1, 'a'), (2, 'b')`, after the VALUES keyword. Rows may differ in length here, see `check_row_arity`.
+    fn parse_values_rows(&mut self) -> Result<Vec<Vec<Expression>>, Error> {
+        let mut rows = Vec::new();
+        loop {
+            self.expect_token(Token::LeftParentheses)?;
+            rows.push(self.parse_expression_tuple_rest()?);
+
+            if let Some(Token::Comma) = self.peek() {
+                self.advance();
+            } else {
+                return Ok(rows);
+            }
+        }
+    }
+
+    /// Parses `ANY (SELECT ...)` or `ALL (SELECT ...)` after a comparison operator has been consumed
+    fn parse_quantified_comparison(&mut self, left: Expression, op: BinaryOperator) -> Result<Expression, Error> {
+        let quantifier = match self.peek() {
+            Some(Token::Keyword(Keyword::All)) => Quantifier::All,
+            _ => Quantifier::Any,
+        };
+        if !op.is_comparison() {
+            return Err(Error::ParserError(format!("{} cannot be used with {}, only comparison operators can", quantifier, op)));
+        }
+        self.advance();
+
+        self.expect_token(Token::LeftParentheses)?;
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::Select))) {
+            return Err(self.unexpected_token("SELECT"));
+        }
+        let subquery = self.parse_select_body()?;
+        self.expect_token(Token::RightParentheses)?;
+
+        Ok(Expression::Quantified {
+            left: Box::new(left),
+            op,
+            quantifier,
+            subquery: Box::new(subquery),
+        })
+    }
+
+    /// Parses the rest of a `CASE` expression after the CASE keyword: an optional operand, one or more `WHEN ... THEN ...` branches, an optional `ELSE`, and END. With an operand each WHEN gives a value compared to it, without one each WHEN is a condition.
+    fn parse_case(&mut self) -> Result<Expression, Error> {
+        let operand = if let Some(Token::Keyword(Keyword::When)) = self.peek() {
+            None
+        } else {
+            Some(Box::new(self.parse_expression()?))
+        };
+
+        let mut branches = Vec::new();
+        while let Some(Token::Keyword(Keyword::When)) = self.peek() {
+            self.advance();
+            let condition = self.parse_expression()?;
+            self.expect_keyword(Keyword::Then)?;
+            branches.push((condition, self.parse_expression()?));
+        }
+        if branches.is_empty() {
+            return Err(self.unexpected_token("WHEN"));
+        }
+
+        let else_result = if let Some(Token::Keyword(Keyword::Else)) = self.peek() {
+            self.advance();
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
+        self.expect_keyword(Keyword::End)?;
+
+        Ok(Expression::Case { operand, branches, else_result })
+    }
+
+    /// Checks a number literal against the configured bounds and consumes it. `negated` is set when a minus sign directly before the literal has already been consumed; the bounds then apply to the negative value.
+    fn parse_number(&mut self, n: u64, negated: bool) -> Result<Expression, Error> {
+        let value = if negated { 0i64.checked_sub_unsigned(n) } else { i64::try_from(n).ok() };
+        let value = value.ok_or_else(|| Error::ParserError(format!("Number {} is too large", n)))?;
+        if let Some((min, max)) = self.int_bounds {
+            if value < min || value > max {
+                return Err(Error::ParserError(format!("Number {} is outside the allowed range {}..={}", value, min, max)));
+            }
+        }
+        self.advance();
+        // The magnitude of the smallest number has no positive literal, so `-9223372036854775808` is the one negative number read as a single literal
+        if negated && value != i64::MIN {
+            return Ok(Expression::UnaryOperation {
+                operand: Box::new(Expression::Number(-value)),
+                operator: UnaryOperator::Minus,
+            });
+        }
+        Ok(Expression::Number(value))
+    }
+
+    /// Records a `?` or `?N` placeholder that has just been consumed, rejecting a mix of the two styles
+    fn parse_placeholder(&mut self, index: Option<u32>) -> Result<Expression, Error> {
+        match index {
+            Some(0) => return Err(Error::ParserError("Parameter indexes start at 1".to_string())),
+            Some(_) => self.numbered_parameters = true,
+            None => self.anonymous_parameters += 1,
+        }
+        if self.numbered_parameters && self.anonymous_parameters > 0 {
+            return Err(Error::ParserError("Cannot mix anonymous '?' and numbered '?N' parameters".to_string()));
+        }
+        Ok(Expression::Parameter(index))
+    }
+
+    /// Parses the rest of an `INTERVAL '3' DAY` literal after the INTERVAL keyword
+    fn parse_interval(&mut self) -> Result<Expression, Error> {
+        let value = match self.peek() {
+            Some(Token::String(s)) => {
+                let s = s.clone();
+                self.advance();
+                s
+            }
+            Some(token) => return Err(Error::ParserError(format!("Expected interval value string, found {:?}", token))),
+            None => return Err(Error::UnexpectedEOF),
+        };
+        let unit = match self.peek() {
+            Some(Token::Keyword(Keyword::Year)) => IntervalUnit::Year,
+            Some(Token::Keyword(Keyword::Month)) => IntervalUnit::Month,
+            Some(Token::Keyword(Keyword::Day)) => IntervalUnit::Day,
+            Some(Token::Keyword(Keyword::Hour)) => IntervalUnit::Hour,
+            Some(Token::Keyword(Keyword::Minute)) => IntervalUnit::Minute,
+            Some(Token::Keyword(Keyword::Second)) => IntervalUnit::Second,
+            Some(token) => return Err(Error::ParserError(format!("Expected interval unit, found {:?}", token))),
+            None => return Err(Error::UnexpectedEOF),
+        };
+        self.advance();
+        Ok(Expression::Interval { value, unit })
+    }
+
+    fn parse_prefix_expression(&mut self) -> Result<Expression, Error> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.parse_number(n, false)
+            }
+            Some(Token::String(s)) => {
+                let s = s.clone();
+                self.advance();
+                Ok(Expression::String(s))
+            }
+            Some(Token::Identifier(i)) => {
+                let i = i.clone();
+                self.advance();
+                self.parse_name_expression(i)
+            }
+            Some(Token::Placeholder(index)) => {
+                let index = *index;
+                self.advance();
+                self.parse_placeholder(index)
+            }
+            Some(Token::NamedParameter(name)) => {
+                let name = name.clone();
+                self.advance();
+                Ok(Expression::NamedParameter(name))
+            }
+            Some(Token::Variable(name)) => {
+                let name = name.clone();
+                self.advance();
+                Ok(Expression::Variable(name))
+            }
+            Some(Token::Keyword(Keyword::True)) => {
+                self.advance();
+                Ok(Expression::Bool(true))
+            }
+            Some(Token::Keyword(Keyword::False)) => {
+                self.advance();
+                Ok(Expression::Bool(false))
+            }
+            Some(Token::Keyword(Keyword::Unknown)) => {
+                self.advance();
+                Ok(Expression::Unknown)
+            }
+            Some(Token::Keyword(keyword @ (Keyword::CurrentDate | Keyword::CurrentTime | Keyword::CurrentTimestamp))) => {
+                let value = match keyword {
+                    Keyword::CurrentDate => CurrentValue::Date,
+                    Keyword::CurrentTime => CurrentValue::Time,
+                    _ => CurrentValue::Timestamp,
+                };
+                self.advance();
+                Ok(Expression::Current(value))
+            }
+            Some(Token::Keyword(Keyword::Case)) => {
+                self.advance();
+                self.parse_case()
+            }
+            Some(Token::Keyword(Keyword::Interval)) => {
+                self.advance();
+                self.parse_interval()
+            }
+            Some(Token::LeftParentheses) => {
+                self.advance();
+                // A comma after the first expression makes a row constructor, `(a, b)`, rather than a grouped expression
+                let mut items = self.parse_expression_tuple_rest()?;
+                if items.len() == 1 {
+                    Ok(items.remove(0))
+                } else {
+                    Ok(Expression::Row(items))
+                }
+            }
+            // A minus directly before a number is checked against the bounds together with it
+            Some(Token::Minus) => match self.peek_ahead(1) {
+                Some(&Token::Number(n)) => {
+                    self.advance();
+                    self.parse_number(n, true)
+                }
+                _ => self.parse_unary_operation(UnaryOperator::Minus, PREFIX_PRECEDENCE),
+            },
+            Some(Token::Plus) => self.parse_unary_operation(UnaryOperator::Plus, PREFIX_PRECEDENCE),
+            // NOT binds looser than comparisons and predicates but tighter than AND, so `NOT a AND b` negates only `a`
+            Some(Token::Keyword(Keyword::Not)) => self.parse_unary_operation(UnaryOperator::Not, BinaryOperator::And.precedence() + 1),
+            // A non-reserved keyword in prefix position can only be a column name
+            Some(Token::Keyword(keyword)) if !keyword.is_reserved() => {
+                let name = keyword.as_str().to_string();
+                self.advance();
+                self.parse_name_expression(name)
+            }
+            Some(token) => Err(Error::ParserError(format!("Unexpected token in prefix position: {:?}", token))),
+            None => Err(Error::UnexpectedEOF),
+        }
+    }
+
+    /// Consumes a prefix operator and parses its operand, which takes every operator binding at least as tightly as `precedence`
+    fn parse_unary_operation(&mut self, operator: UnaryOperator, precedence: u8) -> Result<Expression, Error> {
+        self.advance();
+        let operand = self.parse_binary_expression(precedence)?;
+        Ok(Expression::UnaryOperation {
+            operand: Box::new(operand),
+            operator,
+        })
+    }
+
+    /// Parses what follows a name that has just been consumed: a function call if a parenthesis comes next, otherwise a plain identifier
+    fn parse_name_expression(&mut self, name: String) -> Result<Expression, Error> {
+        if let Some(Token::LeftParentheses) = self.peek() {
+            return self.parse_function_call(name);
+        }
+        Ok(Expression::Identifier(name))
+    }
+
+    fn parse_function_call(&mut self, name: String) -> Result<Expression, Error> {
+        // Consume the opening parenthesis
+        self.advance();
+
+        let mut args = Vec::new();
+        if let Some(Token::RightParentheses) = self.peek() {
+            self.advance();
+        } else if matches!(self.peek(), Some(Token::Star | Token::Wildcard)) && self.peek_ahead(1) == Some(&Token::RightParentheses) {
+            // COUNT(*) counts rows; the star is kept as the `*` identifier, as it is in a select list
+            self.advance();
+            self.advance();
+            args.push(Expression::Identifier("*".to_string()));
+        } else {
+            loop {
+                args.push(self.parse_expression()?);
+                match self.peek() {
+                    Some(Token::Comma) => {
+                        self.advance();
+                        if let Some(Token::RightParentheses) = self.peek() {
+                            return Err(Error::ParserError("trailing comma before ')'".to_string()));
+                        }
+                    }
+                    Some(Token::RightParentheses) => {
+                        self.advance();
+                        break;
+                    }
+                    Some(_) => return Err(self.unexpected_token("comma or ')'")),
+                    None => return Err(Error::UnexpectedEOF),
+                }
+            }
+        }
+
+        // Null-handling functions have a fixed shape, so check their arity here
+        match name.as_str() {
+            "COALESCE" if args.is_empty() => {
+                return Err(Error::ParserError("COALESCE requires at least one argument".to_string()));
+            }
+            "NULLIF" if args.len() != 2 => {
+                return Err(Error::ParserError(format!("NULLIF requires exactly two arguments, found {}", args.len())));
+            }
+            _ => {}
+        }
+
+        Ok(Expression::FunctionCall { name, args })
+    }
+
+    fn parse_binary_operator(&mut self) -> Result<BinaryOperator, Error> {
+        let op = match self.peek() {
+            Some(token) => match binary_operator(token) {
+                Some(op) => op,
+                None => return Err(Error::ParserError(format!("Unexpected token in operator position: {:?}", token))),
+            },
+            None => return Err(Error::UnexpectedEOF),
+        };
+        self.advance();
+        Ok(op)
+    }
+
+    fn get_binary_precedence(&self, token: &Token) -> Option<u8> {
+        binary_operator(token).map(|op| op.precedence())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current)
+    }
+
+    /// Whether every statement in the input has been parsed, leaving only the end of input
+    pub fn is_at_end(&self) -> bool {
+        matches!(self.peek(), None | Some(Token::Eof))
+    }
+
+    /// Returns the index of the token the parser is looking at, which after an error is the token that caused it
+    pub fn position(&self) -> usize {
+        self.current
+    }
+
+    /// Looks `n` tokens past the current one without consuming anything; `peek_ahead(0)` is the same as `peek()`
+    fn peek_ahead(&self, n: usize) -> Option<&Token> {
+        self.tokens.iter().skip(self.current).filter(|token| !matches!(token, Token::Comment(_))).nth(n)
+    }
+
+    fn advance(&mut self) {
+        self.consumed += 1;
+        // Once over budget, act as if the input ended so parsing unwinds straight away; `parse` reports the real reason
+        self.current = if self.over_budget() { self.tokens.len() } else { self.current + 1 };
+        self.skip_comments();
+    }
+
+    /// Moves past comment tokens, so the grammar never sees them. They only appear when the tokenizer was told to keep comments.
+    fn skip_comments(&mut self) {
+        while let Some(Token::Comment(_)) = self.tokens.get(self.current) {
+            self.current += 1;
+        }
+    }
+
+    fn over_budget(&self) -> bool {
+        matches!(self.token_budget, Some(budget) if self.consumed > budget)
+    }
+
+    fn expect_token(&mut self, expected: Token) -> Result<(), Error> {
+        match self.peek() {
+            Some(token) if token == &expected => {
+                self.advance();
+                Ok(())
+            }
+            Some(_) => Err(self.unexpected_token(format!("{:?}", expected))),
+            None => Err(Error::UnexpectedEOF),
+        }
+    }
+
+    fn unexpected_token(&self, expected: impl Into<String>) -> Error {
+        match self.peek() {
+            Some(token) => Error::UnexpectedToken {
+                expected: expected.into(),
                 found: format!("{:?}", token),
-            }),
+                position: self.spans.get(self.current).copied(),
+                context: self.context(),
+            },
+            None => Error::UnexpectedEOF,
+        }
+    }
+
+    /// Renders the previous, current and next token as SQL text, with the current one marked, for error messages
+    fn context(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(previous) = self.current.checked_sub(1).and_then(|index| self.tokens.get(index)) {
+            parts.push(token_text(previous));
+        }
+        if let Some(token) = self.peek() {
+            parts.push(format!(">>> {} <<<", token_text(token)));
+        }
+        if let Some(next) = self.peek_ahead(1) {
+            parts.push(token_text(next));
+        }
+        parts.join(" ")
+    }
+
+    /// Consumes an identifier and returns its name. `context` says what the name is for, as in "Expected table name, found ...".
+    fn expect_identifier(&mut self, context: &str) -> Result<String, Error> {
+        if let Some(name) = self.peek_identifier() {
+            self.advance();
+            return Ok(name);
+        }
+        match self.peek() {
+            // In strict mode a reserved word gets its own error rather than the generic one
+            Some(Token::Keyword(keyword)) if self.strict => Err(Error::ParserError(match self.dialect.identifier_quote() {
+                // Only suggest quoting when the dialect has a way to quote
+                Some(quote) => format!(
+                    "'{}' is a reserved word; quote it as {}{}{} to use as an identifier",
+                    keyword.as_str(),
+                    quote,
+                    keyword.as_str(),
+                    quote
+                ),
+                None => format!("'{}' is a reserved word and cannot be used as an identifier", keyword.as_str()),
+            })),
+            Some(token) => Err(Error::ParserError(format!("Expected {}, found {:?}", context, token))),
+            None => Err(Error::UnexpectedEOF),
+        }
+    }
+
+    /// Returns the name the current token stands for where a name is expected, without consuming it: the name of an identifier or the spelling of a non-reserved keyword
+    fn peek_identifier(&self) -> Option<String> {
+        match self.peek()? {
+            Token::Identifier(name) => Some(name.clone()),
+            Token::Keyword(keyword) if !keyword.is_reserved() => Some(keyword.as_str().to_string()),
+            _ => None,
+        }
+    }
+
+    /// Parses the operand of a LIKE's ESCAPE clause, which must be a string of exactly one character
+    fn parse_escape_character(&mut self) -> Result<char, Error> {
+        match self.peek() {
+            Some(Token::String(s)) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => {
+                        self.advance();
+                        Ok(c)
+                    }
+                    _ => Err(Error::ParserError(format!("ESCAPE must be a single character, found {:?}", s))),
+                }
+            }
+            Some(token) => Err(Error::ParserError(format!("Expected an escape character string after ESCAPE, found {:?}", token))),
+            None => Err(Error::UnexpectedEOF),
+        }
+    }
+
+    /// Parses what follows TABLESAMPLE: the sampling method and the percentage of rows in parentheses
+    fn parse_table_sample(&mut self) -> Result<TableSample, Error> {
+        let method = match self.peek() {
+            Some(Token::Keyword(Keyword::System)) => SampleMethod::System,
+            Some(Token::Keyword(Keyword::Bernoulli)) => SampleMethod::Bernoulli,
+            Some(_) => return Err(self.unexpected_token("SYSTEM or BERNOULLI")),
+            None => return Err(Error::UnexpectedEOF),
+        };
+        self.advance();
+
+        self.expect_token(Token::LeftParentheses)?;
+        let percentage = match self.peek() {
+            Some(Token::Number(n)) if *n <= 100 => *n,
+            Some(Token::Number(n)) => {
+                return Err(Error::ParserError(format!("TABLESAMPLE percentage must be at most 100, found {}", n)));
+            }
+            Some(_) => return Err(self.unexpected_token("a sampling percentage")),
+            None => return Err(Error::UnexpectedEOF),
+        };
+        self.advance();
+        self.expect_token(Token::RightParentheses)?;
+
+        Ok(TableSample { method, percentage })
+    }
+
+    /// Parses a possibly schema-qualified name such as `users`, `public.users` or `mydb.public.users`. Each part follows the usual identifier rules, so unquoted parts are uppercased and quoted parts keep their case.
+    fn parse_qualified_name(&mut self, context: &str) -> Result<QualifiedName, Error> {
+        let mut parts = vec![self.expect_identifier(context)?];
+        while let Some(Token::Dot) = self.peek() {
+            self.advance();
+            parts.push(self.expect_identifier(context)?);
+        }
+        Ok(QualifiedName::new(parts))
+    }
+
+    fn expect_statement_end(&mut self) -> Result<(), Error> {
+        if self.relaxed && matches!(self.peek(), Some(Token::Eof) | None) {
+            return Ok(());
+        }
+        self.expect_token(Token::Semicolon)
+    }
+
+    fn expect_keyword(&mut self, expected: Keyword) -> Result<(), Error> {
+        match self.peek() {
+            Some(Token::Keyword(keyword)) if keyword == &expected => {
+                self.advance();
+                Ok(())
+            }
+            Some(_) => Err(self.unexpected_token(format!("{:?}", expected))),
             None => Err(Error::UnexpectedEOF),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::tokenizer::Tokenizer;
+/// Reports a bad or missing name inside a FOREIGN KEY constraint as an invalid foreign key rather than a general parser error. `missing` is the message for input that ends where the name should be.
+fn invalid_foreign_key(error: Error, missing: &str) -> Error {
+    match error {
+        Error::ParserError(message) => Error::InvalidForeignKey(message),
+        Error::UnexpectedEOF => Error::InvalidForeignKey(missing.to_string()),
+        other => other,
+    }
+}
+
+/// Checks that every row of a VALUES list has `expected` values, naming the first row that does not, counting from 1
+fn check_row_arity(rows: &[Vec<Expression>], expected: usize) -> Result<(), Error> {
+    match rows.iter().position(|row| row.len() != expected) {
+        Some(index) => Err(Error::ParserError(format!(
+            "VALUES row {} has {} values, expected {}",
+            index + 1,
+            rows[index].len(),
+            expected
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Rejects constraints on one column that contradict each other: `NOT NULL` with `NULL`, and `PRIMARY KEY` with `NULL` or with the redundant `UNIQUE`. `PRIMARY KEY` with `NOT NULL` is redundant too, but common enough to allow.
+fn check_column_constraints(column_name: &str, constraints: &[Constraint]) -> Result<(), Error> {
+    const CONFLICTS: [(Constraint, Constraint); 3] = [
+        (Constraint::NotNull, Constraint::Null),
+        (Constraint::PrimaryKey, Constraint::Null),
+        (Constraint::PrimaryKey, Constraint::Unique),
+    ];
+    let has = |wanted: &Constraint| constraints.iter().any(|constraint| constraint.unnamed() == wanted);
+    match CONFLICTS.iter().find(|(first, second)| has(first) && has(second)) {
+        Some((first, second)) => Err(Error::ParserError(format!(
+            "Column {} cannot be both {} and {}",
+            column_name, first, second
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Returns a token as it would be written in SQL, for the context shown in error messages
+fn token_text(token: &Token) -> String {
+    match token {
+        Token::Keyword(keyword) => keyword.as_str().to_string(),
+        Token::Identifier(name) => name.clone(),
+        Token::String(value) => format!("'{}'", value.replace('\'', "''")),
+        Token::Eof => "end of input".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Maps an operator token to the binary operator it stands for, or `None` if the token is not one
+fn binary_operator(token: &Token) -> Option<BinaryOperator> {
+    let op = match token {
+        Token::Plus => BinaryOperator::Plus,
+        Token::Minus => BinaryOperator::Minus,
+        Token::Star => BinaryOperator::Multiply,
+        Token::Divide => BinaryOperator::Divide,
+        Token::Caret => BinaryOperator::Power,
+        Token::Equal => BinaryOperator::Equal,
+        Token::NotEqual => BinaryOperator::NotEqual,
+        Token::GreaterThan => BinaryOperator::GreaterThan,
+        Token::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
+        Token::LessThan => BinaryOperator::LessThan,
+        Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
+        Token::Keyword(Keyword::And) => BinaryOperator::And,
+        Token::Keyword(Keyword::Or) => BinaryOperator::Or,
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Checks that every foreign key, whether declared on a column or at table level, starts from a declared column, and that a reference back to the same table points at a declared column too
+fn validate_foreign_keys(table_name: &QualifiedName, column_list: &[TableColumn]) -> Result<(), Error> {
+    let is_declared = |name: &str| column_list.iter().any(|col| col.column_name == name);
+
+    for col in column_list {
+        for constraint in &col.constraints {
+            if let Constraint::ForeignKey { column, referenced_table, referenced_column } = constraint.unnamed() {
+                if !is_declared(column) {
+                    return Err(Error::InvalidForeignKey(format!("Column {} not found in table", column)));
+                }
+                if referenced_table == table_name && !is_declared(referenced_column) {
+                    return Err(Error::InvalidForeignKey(format!(
+                        "Referenced column {} not found in table {}", referenced_column, table_name
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenizer;
+
+    fn parse_sql(input: &str) -> Result<Statement, Error> {
+        let mut tokenizer = Tokenizer::new(input);
+        let tokens = tokenizer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).with_spans(tokenizer.spans().to_vec());
+        parser.parse()
+    }
+
+    #[test]
+    fn test_parse_select_basic() {
+        let stmt = parse_sql("SELECT id, name FROM users;").unwrap();
+        match stmt {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, limit, offset } => {
+                assert_eq!(columns.len(), 2);
+                assert!(limit.is_none() && offset.is_none());
+                assert!(groupby.is_none());
+                assert!(having.is_none());
+                assert_eq!(from, FromSource::Table { name: "USERS".into(), only: false, sample: None });
+                assert!(r#where.is_none());
+                assert!(orderby.is_empty());
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_select_whole_statement() {
+        use crate::ast::{ident, num, string, binary, unary, call};
+
+        let stmt = parse_sql("SELECT id, COALESCE(name, 'n/a') FROM users WHERE age * 2 > 30 AND name != 'x' ORDER BY id DESC;").unwrap();
+        assert_eq!(stmt, Statement::Select {
+            columns: vec![
+                ident("ID"),
+                call("COALESCE", vec![ident("NAME"), string("n/a")]),
+            ],
+            from: FromSource::Table { name: "USERS".into(), only: false, sample: None },
+            r#where: Some(binary(
+                binary(binary(ident("AGE"), BinaryOperator::Multiply, num(2)), BinaryOperator::GreaterThan, num(30)),
+                BinaryOperator::And,
+                binary(ident("NAME"), BinaryOperator::NotEqual, string("x")),
+            )),
+            groupby: None,
+            having: None,
+            orderby: vec![unary(UnaryOperator::Desc, ident("ID"))],
+            limit: None,
+            offset: None,
+        });
+    }
+
+    #[test]
+    fn test_parse_group_by_with_rollup() {
+        use crate::ast::ident;
+
+        let stmt = parse_sql("SELECT region, product FROM sales GROUP BY region, product WITH ROLLUP ORDER BY region;").unwrap();
+        match &stmt {
+            Statement::Select { groupby, orderby, .. } => {
+                assert_eq!(groupby, &Some(GroupBy { expressions: vec![ident("REGION"), ident("PRODUCT")], rollup: true }));
+                assert_eq!(orderby.len(), 1);
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT REGION, PRODUCT FROM SALES GROUP BY REGION, PRODUCT WITH ROLLUP ORDER BY REGION");
+
+        let stmt = parse_sql("SELECT region FROM sales GROUP BY region;").unwrap();
+        assert!(matches!(stmt, Statement::Select { groupby: Some(GroupBy { rollup: false, .. }), .. }));
+
+        // A CTE inside the query still starts with WITH
+        assert!(parse_sql("WITH s AS (SELECT region FROM sales GROUP BY region WITH ROLLUP) SELECT region FROM s;").is_ok());
+        assert!(parse_sql("SELECT region FROM sales GROUP BY region WITH;").is_err());
+    }
+
+    #[test]
+    fn test_parse_offset_fetch() {
+        let standard = parse_sql("SELECT a FROM t ORDER BY a OFFSET 10 ROWS FETCH NEXT 5 ROWS ONLY;").unwrap();
+        assert!(matches!(standard, Statement::Select { limit: Some(5), offset: Some(10), .. }));
+        assert_eq!(standard, parse_sql("SELECT a FROM t ORDER BY a LIMIT 5 OFFSET 10;").unwrap());
+        assert_eq!(parse_sql("SELECT a FROM t OFFSET 1 ROW FETCH NEXT 1 ROW ONLY;").unwrap(), parse_sql("SELECT a FROM t LIMIT 1 OFFSET 1;").unwrap());
+
+        assert!(matches!(parse_sql("SELECT a FROM t FETCH NEXT 3 ROWS ONLY;").unwrap(), Statement::Select { limit: Some(3), offset: None, .. }));
+        assert!(matches!(parse_sql("SELECT a FROM t OFFSET 3;").unwrap(), Statement::Select { limit: None, offset: Some(3), .. }));
+        assert_eq!(standard.to_string(), "SELECT A FROM T ORDER BY A LIMIT 5 OFFSET 10");
+
+        assert!(parse_sql("SELECT a FROM t FETCH NEXT 3 ROWS;").is_err());
+        assert!(matches!(
+            parse_sql("SELECT a FROM t LIMIT x;"),
+            Err(Error::ParserError(msg)) if msg == "Expected a row count after LIMIT, found Identifier(\"X\")"
+        ));
+    }
+
+    #[test]
+    fn test_parse_limit_forms() {
+        assert!(matches!(parse_sql("SELECT a FROM t LIMIT 10;").unwrap(), Statement::Select { limit: Some(10), offset: None, .. }));
+        assert!(matches!(parse_sql("SELECT a FROM t LIMIT 10 OFFSET 20;").unwrap(), Statement::Select { limit: Some(10), offset: Some(20), .. }));
+        assert!(matches!(parse_sql("SELECT a FROM t LIMIT 20, 10;").unwrap(), Statement::Select { limit: Some(10), offset: Some(20), .. }));
+    }
+
+    #[test]
+    fn test_error_limit_with_offset_twice() {
+        assert!(matches!(
+            parse_sql("SELECT a FROM t LIMIT 20, 10 OFFSET 5;"),
+            Err(Error::ParserError(msg)) if msg == "OFFSET cannot follow LIMIT with two arguments, which already gives the offset"
+        ));
+        assert!(parse_sql("SELECT a FROM t LIMIT 20,;").is_err());
+    }
+
+    #[test]
+    fn test_parse_select_where() {
+        let stmt = parse_sql("SELECT id FROM users WHERE age >= 18;").unwrap();
+        match stmt {
+            Statement::Select { columns: _, from: _, r#where, groupby: _, having: _, orderby: _, limit: _, offset: _ } => {
+                assert!(r#where.is_some());
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_select_order_by() {
+        let stmt = parse_sql("SELECT id FROM users ORDER BY name ASC, age DESC;").unwrap();
+        match stmt {
+            Statement::Select { columns: _, from: _, r#where: _, groupby: _, having: _, orderby, limit: _, offset: _ } => {
+                assert_eq!(orderby.len(), 2);
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_order_by_nulls() {
+        use crate::ast::{ident, unary};
+
+        let stmt = parse_sql("SELECT id FROM users ORDER BY score DESC NULLS FIRST, name ASC, age NULLS LAST, id;").unwrap();
+        match &stmt {
+            Statement::Select { orderby, .. } => {
+                assert_eq!(orderby, &vec![
+                    unary(UnaryOperator::NullsFirst, unary(UnaryOperator::Desc, ident("SCORE"))),
+                    unary(UnaryOperator::Asc, ident("NAME")),
+                    unary(UnaryOperator::NullsLast, ident("AGE")),
+                    ident("ID"),
+                ]);
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT ID FROM USERS ORDER BY SCORE DESC NULLS FIRST, NAME ASC, AGE NULLS LAST, ID");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        // NULLS, FIRST and LAST are still usable as column names
+        assert!(parse_sql("SELECT first FROM t ORDER BY last NULLS FIRST;").is_ok());
+        assert!(parse_sql("SELECT id FROM users ORDER BY score NULLS;").is_err());
+        assert!(parse_sql("SELECT id FROM users ORDER BY score DESC NULLS DESC;").is_err());
+    }
+
+    #[test]
+    fn test_parse_case_in_order_by_and_group_by() {
+        use crate::ast::{binary, ident, num, string, unary};
+
+        let searched = Expression::Case {
+            operand: None,
+            branches: vec![(ident("A"), num(1))],
+            else_result: Some(Box::new(num(2))),
+        };
+        let stmt = parse_sql("SELECT id FROM t ORDER BY CASE WHEN a THEN 1 ELSE 2 END DESC, id;").unwrap();
+        match &stmt {
+            Statement::Select { orderby, .. } => {
+                assert_eq!(orderby, &vec![unary(UnaryOperator::Desc, searched.clone()), ident("ID")]);
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT ID FROM T ORDER BY CASE WHEN A THEN 1 ELSE 2 END DESC, ID");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        // The simple form compares an operand against each WHEN value
+        let stmt = parse_sql(
+            "SELECT CASE status WHEN 1 THEN 'new' WHEN 2 THEN 'done' END, COUNT(id) FROM t GROUP BY CASE status WHEN 1 THEN 'new' WHEN 2 THEN 'done' END ORDER BY CASE WHEN age > 18 THEN 0 END ASC NULLS LAST;",
+        )
+        .unwrap();
+        let simple = Expression::Case {
+            operand: Some(Box::new(ident("STATUS"))),
+            branches: vec![(num(1), string("new")), (num(2), string("done"))],
+            else_result: None,
+        };
+        match &stmt {
+            Statement::Select { columns, groupby: Some(groupby), orderby, .. } => {
+                assert_eq!(columns[0], simple);
+                assert_eq!(groupby.expressions, vec![simple.clone()]);
+                let adult = Expression::Case {
+                    operand: None,
+                    branches: vec![(binary(ident("AGE"), BinaryOperator::GreaterThan, num(18)), num(0))],
+                    else_result: None,
+                };
+                assert_eq!(orderby, &vec![unary(UnaryOperator::NullsLast, unary(UnaryOperator::Asc, adult))]);
+            }
+            other => panic!("Expected grouped Select statement, got {:?}", other),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        assert!(parse_sql("SELECT id FROM t ORDER BY CASE ELSE 1 END;").is_err());
+        assert!(parse_sql("SELECT id FROM t ORDER BY CASE WHEN a THEN 1 DESC;").is_err());
+        assert!(parse_sql("SELECT id FROM t ORDER BY CASE WHEN a 1 END;").is_err());
+    }
+
+    #[test]
+    fn test_error_order_by_trailing_comma() {
+        assert!(parse_sql("SELECT id FROM users ORDER BY name DESC NULLS LAST,;").is_err());
+        assert!(parse_sql("SELECT id FROM users ORDER BY name, LIMIT 5;").is_err());
+        assert!(parse_sql("SELECT id FROM users ORDER BY name,").is_err());
+    }
+
+    #[test]
+    fn test_parse_select_star() {
+        let stmt = parse_sql("SELECT * FROM users;").unwrap();
+        match stmt {
+            Statement::Select { columns, from: _, r#where: _, groupby: _, having: _, orderby: _, limit: _, offset: _ } => {
+                assert_eq!(columns.len(), 1);
+                assert!(matches!(&columns[0], Expression::Identifier(s) if s == "*"));
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_derived_table() {
+        use crate::ast::ident;
+
+        let stmt = parse_sql("SELECT x FROM (SELECT id AS x FROM users) AS sub;").unwrap();
+        assert_eq!(stmt, Statement::Select {
+            columns: vec![ident("X")],
+            from: FromSource::Derived {
+                query: Box::new(Statement::Select {
+                    columns: vec![Expression::Alias { expr: Box::new(ident("ID")), alias: "X".to_string() }],
+                    from: FromSource::Table { name: "USERS".into(), only: false, sample: None },
+                    r#where: None,
+                    groupby: None,
+                    having: None,
+                    orderby: vec![],
+                    limit: None,
+                    offset: None,
+                }),
+                alias: "SUB".to_string(),
+            },
+            r#where: None,
+            groupby: None,
+            having: None,
+            orderby: vec![],
+            limit: None,
+            offset: None,
+        });
+    }
+
+    #[test]
+    fn test_error_derived_table_without_alias() {
+        assert!(matches!(
+            parse_sql("SELECT x FROM (SELECT id FROM users);"),
+            Err(Error::ParserError(msg)) if msg == "A derived table must have an alias"
+        ));
+        assert!(matches!(
+            parse_sql("SELECT x FROM (users) AS sub;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_select_alias() {
+        use crate::ast::{binary, ident, num};
+
+        let stmt = parse_sql("SELECT price * 2 AS doubled, name FROM items;").unwrap();
+        match &stmt {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns[0], Expression::Alias {
+                    expr: Box::new(binary(ident("PRICE"), BinaryOperator::Multiply, num(2))),
+                    alias: "DOUBLED".to_string(),
+                });
+                assert_eq!(columns[1], ident("NAME"));
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT (PRICE * 2) AS DOUBLED, NAME FROM ITEMS");
+        assert!(parse_sql("SELECT price AS FROM items;").is_err());
+    }
+
+    #[test]
+    fn test_parse_values() {
+        use crate::ast::{num, string};
+
+        let stmt = parse_sql("VALUES (1, 'a'), (2, 'b');").unwrap();
+        assert_eq!(stmt, Statement::Values(vec![
+            vec![num(1), string("a")],
+            vec![num(2), string("b")],
+        ]));
+    }
+
+    #[test]
+    fn test_error_values_arity_mismatch() {
+        assert!(matches!(
+            parse_sql("VALUES (1, 'a'), (2);"),
+            Err(Error::ParserError(msg)) if msg == "VALUES row 2 has 1 values, expected 2"
+        ));
+        assert!(parse_sql("VALUES ();").is_err());
+        assert!(parse_sql("VALUES (1),;").is_err());
+    }
+
+    #[test]
+    fn test_parse_insert() {
+        use crate::ast::{num, string};
+
+        let stmt = parse_sql("INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b');").unwrap();
+        assert_eq!(stmt, Statement::Insert {
+            table_name: "USERS".into(),
+            columns: vec!["ID".to_string(), "NAME".to_string()],
+            rows: vec![vec![num(1), string("a")], vec![num(2), string("b")]],
+        });
+        assert_eq!(stmt.to_string(), "INSERT INTO USERS (ID, NAME) VALUES (1, 'a'), (2, 'b')");
+
+        assert!(matches!(
+            parse_sql("INSERT INTO users VALUES (1);").unwrap(),
+            Statement::Insert { columns, .. } if columns.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_error_insert_row_arity() {
+        assert!(matches!(
+            parse_sql("INSERT INTO t (a, b) VALUES (1, 2), (3), (4, 5, 6);"),
+            Err(Error::ParserError(msg)) if msg == "VALUES row 2 has 1 values, expected 2"
+        ));
+        assert!(matches!(
+            parse_sql("INSERT INTO t (a, b) VALUES (1, 2), (4, 5, 6);"),
+            Err(Error::ParserError(msg)) if msg == "VALUES row 2 has 3 values, expected 2"
+        ));
+        // The column list sets the expected count even for the first row
+        assert!(matches!(
+            parse_sql("INSERT INTO t (a, b) VALUES (1);"),
+            Err(Error::ParserError(msg)) if msg == "VALUES row 1 has 1 values, expected 2"
+        ));
+        assert!(matches!(
+            parse_sql("INSERT INTO t VALUES (1, 2), (3);"),
+            Err(Error::ParserError(msg)) if msg == "VALUES row 2 has 1 values, expected 2"
+        ));
+    }
+
+    #[test]
+    fn test_parse_create_view() {
+        let stmt = parse_sql("CREATE VIEW v (a, b) AS SELECT x, y + 1 FROM t;").unwrap();
+        match &stmt {
+            Statement::CreateView { view_name, columns, query } => {
+                assert_eq!(view_name.name(), "V");
+                assert_eq!(columns, &vec!["A".to_string(), "B".to_string()]);
+                assert_eq!(**query, parse_sql("SELECT x, y + 1 FROM t;").unwrap());
+            }
+            other => panic!("Expected CreateView statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "CREATE VIEW V (A, B) AS SELECT X, (Y + 1) FROM T");
+
+        let stmt = parse_sql("CREATE VIEW v AS SELECT x FROM t WHERE x > 1;").unwrap();
+        assert!(matches!(stmt, Statement::CreateView { columns, .. } if columns.is_empty()));
+        assert!(parse_sql("CREATE VIEW v (a, b) AS SELECT * FROM t;").is_ok());
+    }
+
+    #[test]
+    fn test_error_create_view_column_count() {
+        assert!(matches!(
+            parse_sql("CREATE VIEW v (a, b, c) AS SELECT x, y FROM t;"),
+            Err(Error::ParserError(msg)) if msg == "View V names 3 columns, but its query selects 2"
+        ));
+        assert!(parse_sql("CREATE VIEW v () AS SELECT x FROM t;").is_err());
+        assert!(parse_sql("CREATE VIEW v SELECT x FROM t;").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_table_as() {
+        let stmt = parse_sql("CREATE TABLE snapshot AS SELECT id, name FROM users WHERE active = TRUE;").unwrap();
+        match &stmt {
+            Statement::CreateTableAs { table_name, query } => {
+                assert_eq!(table_name.name(), "SNAPSHOT");
+                assert_eq!(**query, parse_sql("SELECT id, name FROM users WHERE active = TRUE;").unwrap());
+            }
+            other => panic!("Expected CreateTableAs statement, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "CREATE TABLE SNAPSHOT AS SELECT ID, NAME FROM USERS WHERE (ACTIVE = true)");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        // The column-list form is unaffected
+        assert!(matches!(
+            parse_sql("CREATE TABLE snapshot (id INT, name VARCHAR(10));"),
+            Ok(Statement::CreateTable { column_list, .. }) if column_list.len() == 2
+        ));
+        assert!(parse_sql("CREATE TABLE snapshot AS VALUES (1);").is_err());
+    }
+
+    #[test]
+    fn test_parse_set_variable() {
+        use crate::ast::{binary, ident, num};
+
+        let stmt = parse_sql("SET @counter = counter + 1;").unwrap();
+        assert_eq!(stmt, Statement::SetVariable {
+            name: "counter".to_string(),
+            value: binary(ident("COUNTER"), BinaryOperator::Plus, num(1)),
+        });
+        assert_eq!(stmt.to_string(), "SET @counter = (COUNTER + 1)");
+        assert!(parse_sql("SET counter = 1;").is_err());
+    }
+
+    #[test]
+    fn test_parse_variable_reference() {
+        let stmt = parse_sql("SELECT @counter FROM t WHERE id = @id;").unwrap();
+        match &stmt {
+            Statement::Select { columns, r#where: Some(Expression::BinaryOperation { right_operand, .. }), .. } => {
+                assert_eq!(columns, &vec![Expression::Variable("counter".to_string())]);
+                assert_eq!(**right_operand, Expression::Variable("id".to_string()));
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_parse_with_single_cte() {
+        let stmt = parse_sql("WITH recent AS (SELECT * FROM logs WHERE ts > 0) SELECT * FROM recent;").unwrap();
+        match stmt {
+            Statement::With { ctes, body } => {
+                assert_eq!(ctes.len(), 1);
+                assert_eq!(ctes[0].0, "RECENT");
+                assert!(matches!(&ctes[0].1, Statement::Select { from, r#where: Some(_), .. } if *from == FromSource::Table { name: "LOGS".into(), only: false, sample: None }));
+                assert!(matches!(*body, Statement::Select { ref from, .. } if *from == FromSource::Table { name: "RECENT".into(), only: false, sample: None }));
+            }
+            _ => panic!("Expected With statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_with_two_ctes() {
+        let stmt = parse_sql("WITH a AS (SELECT id FROM users), b AS (SELECT id FROM admins ORDER BY id) SELECT id FROM a;").unwrap();
+        match stmt {
+            Statement::With { ctes, .. } => {
+                let names: Vec<&str> = ctes.iter().map(|(name, _)| name.as_str()).collect();
+                assert_eq!(names, vec!["A", "B"]);
+                assert!(matches!(&ctes[1].1, Statement::Select { orderby, .. } if orderby.len() == 1));
+            }
+            _ => panic!("Expected With statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_with_non_select_cte() {
+        assert!(matches!(
+            parse_sql("WITH a AS (CREATE TABLE t (id INT)) SELECT id FROM a;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_transaction_control() {
+        assert_eq!(parse_sql("BEGIN;").unwrap(), Statement::Begin);
+        assert_eq!(parse_sql("BEGIN TRANSACTION;").unwrap(), Statement::Begin);
+        assert_eq!(parse_sql("COMMIT;").unwrap(), Statement::Commit);
+        assert_eq!(parse_sql("rollback;").unwrap(), Statement::Rollback);
+    }
+
+    #[test]
+    fn test_error_transaction_trailing_tokens() {
+        assert!(matches!(
+            parse_sql("BEGIN WORK;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_sql("COMMIT 1;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_sql("ROLLBACK"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_show_tables() {
+        assert_eq!(parse_sql("SHOW TABLES;").unwrap(), Statement::ShowTables);
+        assert!(matches!(parse_sql("SHOW users;"), Err(Error::UnexpectedToken { .. })));
+    }
+
+    #[test]
+    fn test_parse_describe() {
+        let expected = Statement::Describe { table_name: "USERS".into() };
+        assert_eq!(parse_sql("DESCRIBE users;").unwrap(), expected);
+        assert_eq!(parse_sql("DESC users;").unwrap(), expected);
+
+        // DESC keeps its meaning as a sort direction inside ORDER BY
+        let stmt = parse_sql("SELECT id FROM users ORDER BY id DESC;").unwrap();
+        assert!(matches!(stmt, Statement::Select { orderby, .. }
+            if matches!(&orderby[0], Expression::UnaryOperation { operator: UnaryOperator::Desc, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_create_table_basic() {
+        let stmt = parse_sql("CREATE TABLE users (id INT, name VARCHAR(255));").unwrap();
+        match stmt {
+            Statement::CreateTable { table_name, column_list, .. } => {
+                assert_eq!(table_name.name(), "USERS");
+                assert_eq!(column_list.len(), 2);
+                assert!(matches!(column_list[0].column_type, DBType::Int));
+                assert!(matches!(column_list[1].column_type, DBType::Varchar(255)));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_table_boolean_alias() {
+        let stmt = parse_sql("CREATE TABLE users (is_active BOOLEAN NOT NULL, is_admin BOOL);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_type, DBType::Bool);
+                assert_eq!(column_list[1].column_type, DBType::Bool);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_table_integer_family() {
+        let stmt = parse_sql("CREATE TABLE metrics (a SMALLINT, b INT, c BIGINT PRIMARY KEY);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_type, DBType::SmallInt);
+                assert_eq!(column_list[1].column_type, DBType::Int);
+                assert_eq!(column_list[2].column_type, DBType::BigInt);
+                assert!(column_list[2].constraints.contains(&Constraint::PrimaryKey));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_bigint_with_length() {
+        assert!(parse_sql("CREATE TABLE metrics (a BIGINT(20));").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_table_constraints() {
+        let stmt = parse_sql("CREATE TABLE users (
+            id INT PRIMARY KEY,
+            email VARCHAR(255) NOT NULL,
+            age INT CHECK(age >= 18)
+        );").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list.len(), 3);
+                assert!(column_list[0].constraints.contains(&Constraint::PrimaryKey));
+                assert!(column_list[1].constraints.contains(&Constraint::NotNull));
+                assert!(matches!(&column_list[2].constraints[0], Constraint::Check(_)));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_table_foreign_key() {
+        let stmt = parse_sql("CREATE TABLE orders (
+            id INT PRIMARY KEY,
+            user_id INT,
+            FOREIGN KEY (user_id) REFERENCES users(id)
+        );").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert!(matches!(&column_list[1].constraints[0], 
+                    Constraint::ForeignKey { column, referenced_table, referenced_column }
+                    if column == "USER_ID" && referenced_table.name() == "USERS" && referenced_column == "ID"
+                ));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_named_check_constraint() {
+        let stmt = parse_sql("CREATE TABLE users (age INT CONSTRAINT chk_age CHECK (age >= 0) NOT NULL);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].constraints.len(), 2);
+                assert!(matches!(&column_list[0].constraints[0],
+                    Constraint::Named { name, constraint } if name == "CHK_AGE" && matches!(**constraint, Constraint::Check(_))
+                ));
+                assert_eq!(column_list[0].constraints[1], Constraint::NotNull);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_named_foreign_key() {
+        let stmt = parse_sql("CREATE TABLE orders (
+            user_id INT,
+            CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users(id)
+        );").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert!(matches!(&column_list[0].constraints[0],
+                    Constraint::Named { name, constraint } if name == "FK_USER" && matches!(**constraint, Constraint::ForeignKey { .. })
+                ));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_table_level_check() {
+        let stmt = parse_sql("CREATE TABLE ranges (low INT, high INT, CHECK (low < high), CONSTRAINT chk_low CHECK (low >= 0));").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, constraints, .. } => {
+                assert_eq!(column_list.len(), 2);
+                assert!(column_list.iter().all(|col| col.constraints.is_empty()));
+                assert_eq!(constraints.len(), 2);
+                assert_eq!(constraints[0], Constraint::Check(Expression::BinaryOperation {
+                    left_operand: Box::new(Expression::Identifier("LOW".to_string())),
+                    operator: BinaryOperator::LessThan,
+                    right_operand: Box::new(Expression::Identifier("HIGH".to_string())),
+                }));
+                assert!(matches!(&constraints[1], Constraint::Named { name, .. } if name == "CHK_LOW"));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_table_level_check_first() {
+        let stmt = parse_sql("CREATE TABLE ranges (CHECK (low < high), low INT, high INT);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, constraints, .. } => {
+                assert_eq!(column_list.len(), 2);
+                assert_eq!(constraints.len(), 1);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_auto_increment() {
+        let stmt = parse_sql("CREATE TABLE users (id INT PRIMARY KEY AUTO_INCREMENT, name VARCHAR(50));").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_type, DBType::Int);
+                assert_eq!(column_list[0].constraints, vec![Constraint::PrimaryKey, Constraint::AutoIncrement]);
+                assert!(column_list[1].constraints.is_empty());
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_serial() {
+        let stmt = parse_sql("CREATE TABLE users (id SERIAL PRIMARY KEY);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_type, DBType::Int);
+                assert_eq!(column_list[0].constraints, vec![Constraint::AutoIncrement, Constraint::PrimaryKey]);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_boolean_default() {
+        use crate::ast::{ident, boolean, binary};
+        let stmt = parse_sql("CREATE TABLE t (flag BOOL DEFAULT TRUE NOT NULL, other BOOL CHECK (other = FALSE));").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].constraints, vec![Constraint::Default(Expression::Bool(true)), Constraint::NotNull]);
+                assert_eq!(column_list[1].constraints, vec![Constraint::Check(binary(ident("OTHER"), BinaryOperator::Equal, boolean(false)))]);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+    }
+
+    #[test]
+    fn test_default_and_not_null_in_either_order() {
+        use crate::ast::{num, string};
+        let columns = |sql: &str| match parse_sql(sql).unwrap() {
+            Statement::CreateTable { column_list, .. } => column_list,
+            other => panic!("Expected CreateTable statement, got {:?}", other),
+        };
+        let default_first = columns("CREATE TABLE t (name VARCHAR(10) DEFAULT 'x' NOT NULL, n INT DEFAULT 1 NOT NULL UNIQUE);");
+        let not_null_first = columns("CREATE TABLE t (name VARCHAR(10) NOT NULL DEFAULT 'x', n INT NOT NULL UNIQUE DEFAULT 1);");
+
+        assert_eq!(default_first[0].constraints, vec![Constraint::Default(string("x")), Constraint::NotNull]);
+        assert_eq!(not_null_first[0].constraints, vec![Constraint::NotNull, Constraint::Default(string("x"))]);
+        // The loop keeps reading constraints after the DEFAULT expression
+        assert_eq!(default_first[1].constraints, vec![Constraint::Default(num(1)), Constraint::NotNull, Constraint::Unique]);
+        for (a, b) in default_first.iter().zip(&not_null_first) {
+            assert_eq!(a.constraints.len(), b.constraints.len());
+            assert!(a.constraints.iter().all(|c| b.constraints.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_parse_generated_column() {
+        use crate::ast::{ident, binary};
+        let stmt = parse_sql("CREATE TABLE t (price INT, qty INT, total INT GENERATED ALWAYS AS (price * qty) STORED, half INT GENERATED ALWAYS AS (price / 2));").unwrap();
+        match &stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[2].constraints, vec![Constraint::Generated {
+                    expr: binary(ident("PRICE"), BinaryOperator::Multiply, ident("QTY")),
+                    stored: true,
+                }]);
+                assert!(matches!(column_list[3].constraints[0], Constraint::Generated { stored: false, .. }));
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+        assert!(parse_sql("CREATE TABLE t (total INT GENERATED AS (1));").is_err());
+    }
+
+    #[test]
+    fn test_parse_collate() {
+        let stmt = parse_sql("CREATE TABLE t (name VARCHAR(100) COLLATE \"utf8_bin\" NOT NULL, code VARCHAR(3) COLLATE nocase);").unwrap();
+        match &stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].constraints, vec![Constraint::Collate("utf8_bin".to_string()), Constraint::NotNull]);
+                assert_eq!(column_list[1].constraints, vec![Constraint::Collate("NOCASE".to_string())]);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+        assert!(parse_sql("CREATE TABLE t (name VARCHAR(10) COLLATE 1);").is_err());
+
+        // A quote inside the collation name is doubled when printed
+        let stmt = parse_sql("CREATE TABLE t (name VARCHAR(10) COLLATE \"odd'name\");").unwrap();
+        assert_eq!(stmt.to_string(), "CREATE TABLE T (NAME VARCHAR(10) COLLATE 'odd''name')");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_parse_current_timestamp_default() {
+        let stmt = parse_sql("CREATE TABLE t (created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL);").unwrap();
+        match &stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_type, DBType::Timestamp);
+                assert_eq!(column_list[0].constraints, vec![
+                    Constraint::Default(Expression::Current(CurrentValue::Timestamp)),
+                    Constraint::NotNull,
+                ]);
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_parse_current_date_and_time() {
+        let stmt = parse_sql("SELECT CURRENT_DATE, current_time FROM t WHERE born < CURRENT_DATE;").unwrap();
+        match &stmt {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns, &vec![Expression::Current(CurrentValue::Date), Expression::Current(CurrentValue::Time)]);
+            }
+            _ => panic!("Expected Select statement"),
+        }
+        assert_eq!(stmt.to_string(), "SELECT CURRENT_DATE, CURRENT_TIME FROM T WHERE (BORN < CURRENT_DATE)");
+    }
+
+    #[test]
+    fn test_column_constraint_conflicts() {
+        let stmt = parse_sql("CREATE TABLE t (id INT NOT NULL PRIMARY KEY, email VARCHAR(50) UNIQUE NOT NULL, note VARCHAR(10) NULL);").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].constraints, vec![Constraint::NotNull, Constraint::PrimaryKey]);
+                assert_eq!(column_list[1].constraints, vec![Constraint::Unique, Constraint::NotNull]);
+                assert_eq!(column_list[2].constraints, vec![Constraint::Null]);
+            }
+            other => panic!("Expected CREATE TABLE, got {:?}", other),
+        }
+
+        for (sql, message) in [
+            ("CREATE TABLE t (a INT NOT NULL NULL);", "Column A cannot be both NOT NULL and NULL"),
+            ("CREATE TABLE t (a INT NULL PRIMARY KEY);", "Column A cannot be both PRIMARY KEY and NULL"),
+            ("CREATE TABLE t (a INT UNIQUE CONSTRAINT pk PRIMARY KEY);", "Column A cannot be both PRIMARY KEY and UNIQUE"),
+        ] {
+            assert!(matches!(parse_sql(sql), Err(Error::ParserError(msg)) if msg == message), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_error_not_without_null() {
+        assert!(matches!(
+            parse_sql("CREATE TABLE t (id INT NOT TRUE);"),
+            Err(Error::ParserError(msg)) if msg == "Expected NULL after NOT in a column definition, found Keyword(True)"
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE t (id INT DEFAULT 1 NOT 2);"),
+            Err(Error::ParserError(msg)) if msg.ends_with("found Number(2)")
+        ));
+    }
+
+    #[test]
+    fn test_error_constraint_without_body() {
+        assert!(matches!(
+            parse_sql("CREATE TABLE users (age INT CONSTRAINT chk_age);"),
+            Err(Error::ParserError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_sql_not_equal() {
+        use crate::ast::{ident, binary};
+
+        let stmt = parse_sql("SELECT id FROM t WHERE a <> b;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, binary(ident("A"), BinaryOperator::NotEqual, ident("B")));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_double_equal() {
+        use crate::ast::{ident, binary};
+
+        let stmt = parse_sql("SELECT a == b FROM t;").unwrap();
+        match stmt {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns, vec![binary(ident("A"), BinaryOperator::Equal, ident("B"))]);
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_index() {
+        let stmt = parse_sql("CREATE INDEX idx_email ON users (email);").unwrap();
+        assert_eq!(stmt, Statement::CreateIndex {
+            is_unique: false,
+            index_name: "IDX_EMAIL".to_string(),
+            table_name: "USERS".into(),
+            columns: vec![("EMAIL".to_string(), OrderDirection::Asc)],
+            predicate: None,
+        });
+    }
+
+    #[test]
+    fn test_parse_create_index_multiple_columns() {
+        let stmt = parse_sql("CREATE UNIQUE INDEX idx_name ON users (last_name, first_name);").unwrap();
+        assert_eq!(stmt, Statement::CreateIndex {
+            is_unique: true,
+            index_name: "IDX_NAME".to_string(),
+            table_name: "USERS".into(),
+            columns: vec![
+                ("LAST_NAME".to_string(), OrderDirection::Asc),
+                ("FIRST_NAME".to_string(), OrderDirection::Asc),
+            ],
+            predicate: None,
+        });
+    }
+
+    #[test]
+    fn test_parse_unique_partial_multi_column_index() {
+        use crate::ast::{ident, boolean, binary};
+
+        let stmt = parse_sql("CREATE UNIQUE INDEX idx ON t (email, tenant DESC) WHERE deleted = FALSE;").unwrap();
+        assert_eq!(stmt, Statement::CreateIndex {
+            is_unique: true,
+            index_name: "IDX".to_string(),
+            table_name: "T".into(),
+            columns: vec![
+                ("EMAIL".to_string(), OrderDirection::Asc),
+                ("TENANT".to_string(), OrderDirection::Desc),
+            ],
+            predicate: Some(binary(ident("DELETED"), BinaryOperator::Equal, boolean(false))),
+        });
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_parse_create_index_directions() {
+        let stmt = parse_sql("CREATE INDEX idx ON t (a ASC, b DESC, c);").unwrap();
+        match stmt {
+            Statement::CreateIndex { columns, .. } => {
+                assert_eq!(columns, vec![
+                    ("A".to_string(), OrderDirection::Asc),
+                    ("B".to_string(), OrderDirection::Desc),
+                    ("C".to_string(), OrderDirection::Asc),
+                ]);
+            }
+            _ => panic!("Expected CreateIndex statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_partial_index() {
+        use crate::ast::{ident, boolean, binary};
+
+        let stmt = parse_sql("CREATE INDEX idx ON t (a) WHERE active = TRUE;").unwrap();
+        match stmt {
+            Statement::CreateIndex { predicate, .. } => {
+                assert_eq!(predicate, Some(binary(ident("ACTIVE"), BinaryOperator::Equal, boolean(true))));
+            }
+            _ => panic!("Expected CreateIndex statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_create_index_without_columns() {
+        assert!(matches!(
+            parse_sql("CREATE INDEX idx ON users ();"),
+            Err(Error::ParserError(_))
+        ));
+        assert!(matches!(
+            parse_sql("CREATE INDEX idx ON users (a b);"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_inline_foreign_key_unknown_column() {
+        assert!(matches!(
+            parse_sql("CREATE TABLE orders (user_id INT FOREIGN KEY (owner_id) REFERENCES users(id));"),
+            Err(Error::InvalidForeignKey(msg)) if msg == "Column OWNER_ID not found in table"
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE orders (id INT, FOREIGN KEY (owner_id) REFERENCES users(id));"),
+            Err(Error::InvalidForeignKey(msg)) if msg == "Column OWNER_ID not found in table"
+        ));
+    }
+
+    #[test]
+    fn test_error_self_referencing_foreign_key() {
+        assert!(matches!(
+            parse_sql("CREATE TABLE employees (id INT, manager_id INT, FOREIGN KEY (manager_id) REFERENCES employees(boss));"),
+            Err(Error::InvalidForeignKey(msg)) if msg.contains("BOSS")
+        ));
+        assert!(parse_sql("CREATE TABLE employees (id INT, manager_id INT, FOREIGN KEY (manager_id) REFERENCES employees(id));").is_ok());
+        assert!(parse_sql("CREATE TABLE orders (user_id INT FOREIGN KEY (user_id) REFERENCES users(id));").is_ok());
+    }
+
+    #[test]
+    fn test_parse_expressions() {
+        let stmt = parse_sql("SELECT id * 2 + 3, (age - 18) / 2 FROM users;").unwrap();
+        match stmt {
+            Statement::Select { columns, from: _, r#where: _, groupby: _, having: _, orderby: _, limit: _, offset: _ } => {
+                assert_eq!(columns.len(), 2);
+                assert!(matches!(&columns[0], Expression::BinaryOperation { .. }));
+                assert!(matches!(&columns[1], Expression::BinaryOperation { .. }));
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        let stmt = parse_sql("SELECT id FROM logs WHERE age > INTERVAL '7' DAY;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { right_operand, .. }), .. } => {
+                assert_eq!(*right_operand, Expression::Interval {
+                    value: "7".to_string(),
+                    unit: IntervalUnit::Day,
+                });
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+
+        let stmt = parse_sql("SELECT INTERVAL '3' MONTH, INTERVAL '30' SECOND FROM logs;").unwrap();
+        match stmt {
+            Statement::Select { columns, .. } => {
+                assert!(matches!(&columns[0], Expression::Interval { unit: IntervalUnit::Month, .. }));
+                assert!(matches!(&columns[1], Expression::Interval { unit: IntervalUnit::Second, .. }));
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_interval_missing_unit() {
+        assert!(matches!(
+            parse_sql("SELECT INTERVAL '7' FROM logs;"),
+            Err(Error::ParserError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_function_call() {
+        let stmt = parse_sql("SELECT COALESCE(nickname, name, 'anonymous'), NULLIF(a, 0) FROM users;").unwrap();
+        match stmt {
+            Statement::Select { columns, .. } => {
+                assert!(matches!(&columns[0], Expression::FunctionCall { name, args } if name == "COALESCE" && args.len() == 3));
+                assert!(matches!(&columns[1], Expression::FunctionCall { name, args } if name == "NULLIF" && args.len() == 2));
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_function_arity() {
+        assert!(matches!(
+            parse_sql("SELECT COALESCE() FROM users;"),
+            Err(Error::ParserError(msg)) if msg.contains("COALESCE")
+        ));
+        assert!(matches!(
+            parse_sql("SELECT NULLIF(a) FROM users;"),
+            Err(Error::ParserError(msg)) if msg.contains("NULLIF")
+        ));
+        assert!(matches!(
+            parse_sql("SELECT NULLIF(a, b, c) FROM users;"),
+            Err(Error::ParserError(msg)) if msg.contains("NULLIF")
+        ));
+    }
+
+    #[test]
+    fn test_parse_anonymous_parameters() {
+        let stmt = parse_sql("SELECT id FROM users WHERE id = ? AND age > ?;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { left_operand, right_operand, .. }), .. } => {
+                assert!(matches!(*left_operand, Expression::BinaryOperation { right_operand: ref r, .. } if **r == Expression::Parameter(None)));
+                assert!(matches!(*right_operand, Expression::BinaryOperation { right_operand: ref r, .. } if **r == Expression::Parameter(None)));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_numbered_parameters() {
+        let stmt = parse_sql("SELECT id FROM users WHERE id = ?1;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { right_operand, .. }), .. } => {
+                assert_eq!(*right_operand, Expression::Parameter(Some(1)));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_named_parameters() {
+        let stmt = parse_sql("SELECT id FROM users WHERE id = :user_id;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { right_operand, .. }), .. } => {
+                assert_eq!(*right_operand, Expression::NamedParameter(":user_id".to_string()));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+
+        let stmt = parse_sql("SELECT id FROM users WHERE id = $1;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { right_operand, .. }), .. } => {
+                assert_eq!(*right_operand, Expression::NamedParameter("$1".to_string()));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_error_mixed_parameters() {
+        assert!(matches!(
+            parse_sql("SELECT id FROM users WHERE id = ? AND age = ?2;"),
+            Err(Error::ParserError(_))
+        ));
+        assert!(matches!(
+            parse_sql("SELECT id FROM users WHERE id = ?0;"),
+            Err(Error::ParserError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_relaxed_without_semicolon() {
+        let stmt = crate::parse_relaxed("SELECT * FROM t").unwrap();
+        assert!(matches!(stmt, Statement::Select { .. }));
+        let stmt = crate::parse_relaxed("SELECT id FROM t WHERE id = 1").unwrap();
+        assert!(matches!(stmt, Statement::Select { r#where: Some(_), .. }));
+        let stmt = crate::parse_relaxed("CREATE TABLE t (id INT);").unwrap();
+        assert!(matches!(stmt, Statement::CreateTable { .. }));
+    }
+
+    #[test]
+    fn test_error_strict_requires_semicolon() {
+        assert!(matches!(
+            parse_sql("SELECT * FROM t"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE work_hours(num_hours INT)"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_trailing_comma() {
+        assert!(matches!(
+            parse_sql("SELECT a, b, FROM t;"),
+            Err(Error::ParserError(msg)) if msg == "trailing comma before FROM"
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE t (a INT, b BOOL,);"),
+            Err(Error::ParserError(msg)) if msg == "trailing comma before ')'"
+        ));
+        assert!(matches!(
+            parse_sql("SELECT COALESCE(a, b,) FROM t;"),
+            Err(Error::ParserError(msg)) if msg == "trailing comma before ')'"
+        ));
+    }
+
+    #[test]
+    fn test_error_position() {
+        match parse_sql("SELECT a b FROM t;") {
+            Err(Error::UnexpectedToken { expected, found, position, context }) => {
+                assert_eq!(expected, "comma or FROM");
+                assert_eq!(found, "Identifier(\"B\")");
+                assert_eq!(position, Some(Span { line: 1, column: 10 }));
+                assert_eq!(context, "A >>> B <<< FROM");
+            }
+            other => panic!("Expected UnexpectedToken error, got {:?}", other),
+        }
+
+        // A missing FROM is reported at the token where FROM was expected
+        match parse_sql("SELECT a, b\nWHERE a = 1;") {
+            Err(Error::UnexpectedToken { expected, found, position, .. }) => {
+                assert_eq!(expected, "comma or FROM");
+                assert_eq!(found, "Keyword(Where)");
+                assert_eq!(position, Some(Span { line: 2, column: 1 }));
+            }
+            other => panic!("Expected UnexpectedToken error, got {:?}", other),
+        }
+
+        let err = parse_sql("SELECT a\n  b FROM t;").unwrap_err();
+        assert!(err.to_string().contains("at 2:3 near: "));
+
+        let tokens = Tokenizer::new("SELECT a FROM t WHERE a > 1 b;").tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let err = parser.parse().unwrap_err();
+        assert!(err.to_string().ends_with("near: 1 >>> B <<< ;"), "{}", err);
+        assert_eq!(parser.position(), 8);
+
+        match parse_sql("SELECT f(a b) FROM t;") {
+            Err(Error::UnexpectedToken { context, .. }) => assert_eq!(context, "A >>> B <<< )"),
+            other => panic!("Expected UnexpectedToken error, got {:?}", other),
+        }
+        match parse_sql("SELECT a FROM t WHERE a = 'it''s' b;") {
+            Err(Error::UnexpectedToken { context, .. }) => assert_eq!(context, "'it''s' >>> B <<< ;"),
+            other => panic!("Expected UnexpectedToken error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_peek_ahead() {
+        let tokens = Tokenizer::new("a IS NOT NULL").tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(parser.peek_ahead(0), Some(&Token::Identifier("A".to_string())));
+        assert_eq!(parser.peek_ahead(2), Some(&Token::Keyword(Keyword::Not)));
+        assert_eq!(parser.peek_ahead(4), Some(&Token::Eof));
+        assert_eq!(parser.peek_ahead(5), None);
+        parser.advance();
+        assert_eq!(parser.peek_ahead(0), Some(&Token::Keyword(Keyword::Is)));
+        assert_eq!(parser.peek_ahead(2), Some(&Token::Keyword(Keyword::Null)));
+    }
+
+    #[test]
+    fn test_associativity() {
+        use crate::ast::{ident, num, binary};
+
+        let stmt = parse_sql("SELECT 2 ^ 3 ^ 2, a - b - c, 2 * 3 ^ 2 FROM t;").unwrap();
+        match stmt {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns[0], binary(num(2), BinaryOperator::Power, binary(num(3), BinaryOperator::Power, num(2))));
+                assert_eq!(columns[1], binary(binary(ident("A"), BinaryOperator::Minus, ident("B")), BinaryOperator::Minus, ident("C")));
+                assert_eq!(columns[2], binary(num(2), BinaryOperator::Multiply, binary(num(3), BinaryOperator::Power, num(2))));
+            }
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_is_null() {
+        use crate::ast::{ident, num, binary};
+
+        let stmt = parse_sql("SELECT id FROM users WHERE email IS NULL AND age IS NOT NULL;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, binary(
+                    Expression::IsNull { operand: Box::new(ident("EMAIL")), negated: false },
+                    BinaryOperator::And,
+                    Expression::IsNull { operand: Box::new(ident("AGE")), negated: true },
+                ));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+
+        let stmt = parse_sql("SELECT id FROM users WHERE a + 1 IS NULL;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, Expression::IsNull {
+                    operand: Box::new(binary(ident("A"), BinaryOperator::Plus, num(1))),
+                    negated: false,
+                });
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_is_unknown() {
+        use crate::ast::{ident, binary};
+
+        let stmt = parse_sql("SELECT id FROM users WHERE (a = b) IS UNKNOWN OR c IS NOT UNKNOWN;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, binary(
+                    Expression::IsUnknown {
+                        operand: Box::new(binary(ident("A"), BinaryOperator::Equal, ident("B"))),
+                        negated: false,
+                    },
+                    BinaryOperator::Or,
+                    Expression::IsUnknown { operand: Box::new(ident("C")), negated: true },
+                ));
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_is_distinct_from() {
+        use crate::ast::{ident, num, binary};
+
+        let stmt = parse_sql("SELECT id FROM t WHERE a IS DISTINCT FROM b + 1 AND c IS NOT DISTINCT FROM d;").unwrap();
+        match &stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(*expr, binary(
+                    Expression::DistinctFrom {
+                        left: Box::new(ident("A")),
+                        right: Box::new(binary(ident("B"), BinaryOperator::Plus, num(1))),
+                        negated: false,
+                    },
+                    BinaryOperator::And,
+                    Expression::DistinctFrom {
+                        left: Box::new(ident("C")),
+                        right: Box::new(ident("D")),
+                        negated: true,
+                    },
+                ));
+            }
+            other => panic!("Expected Select statement with WHERE clause, got {:?}", other),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        assert!(matches!(
+            parse_sql("SELECT id FROM t WHERE a IS DISTINCT b;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_unknown_literal() {
+        let stmt = parse_sql("SELECT UNKNOWN FROM t;").unwrap();
+        match stmt {
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![Expression::Unknown]),
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_in_list() {
+        use crate::ast::{ident, num};
+
+        let stmt = parse_sql("SELECT id FROM users WHERE id IN (1, 2, 3);").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, Expression::InList {
+                    expr: Box::new(ident("ID")),
+                    list: vec![num(1), num(2), num(3)],
+                    negated: false,
+                });
+            }
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+    }
+
+    #[test]
+    fn test_parse_in_subquery() {
+        use crate::ast::ident;
+
+        let stmt = parse_sql("SELECT id FROM users WHERE id IN (SELECT user_id FROM orders);").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::InSubquery { expr, subquery, negated }), .. } => {
+                assert_eq!(*expr, ident("ID"));
+                assert!(!negated);
+                assert!(matches!(*subquery, Statement::Select { ref from, .. } if *from == FromSource::Table { name: "ORDERS".into(), only: false, sample: None }));
+            }
+            other => panic!("Expected IN subquery, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_from_only() {
+        match parse_sql("SELECT * FROM ONLY parent WHERE id = 1;").unwrap() {
+            Statement::Select { from, .. } => {
+                assert_eq!(from, FromSource::Table { name: "PARENT".into(), only: true, sample: None });
+            }
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert!(matches!(
+            parse_sql("SELECT * FROM parent;"),
+            Ok(Statement::Select { from: FromSource::Table { only: false, .. }, .. })
+        ));
+
+        let stmt = parse_sql("SELECT * FROM ONLY public.parent TABLESAMPLE SYSTEM (10);").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT * FROM ONLY PUBLIC.PARENT TABLESAMPLE SYSTEM (10)");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+        assert!(parse_sql("SELECT * FROM ONLY (SELECT a FROM t) AS d;").is_err());
+    }
+
+    #[test]
+    fn test_parse_table_sample() {
+        let stmt = parse_sql("SELECT id FROM big_table TABLESAMPLE SYSTEM (10) WHERE id > 5;").unwrap();
+        match &stmt {
+            Statement::Select { from: FromSource::Table { name, sample, .. }, r#where, .. } => {
+                assert_eq!(name.name(), "BIG_TABLE");
+                assert_eq!(sample, &Some(TableSample { method: SampleMethod::System, percentage: 10 }));
+                assert!(r#where.is_some());
+            }
+            other => panic!("Expected SELECT from a table, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT ID FROM BIG_TABLE TABLESAMPLE SYSTEM (10) WHERE (ID > 5)");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        let stmt = parse_sql("SELECT id FROM t TABLESAMPLE BERNOULLI (50);").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::Select { from: FromSource::Table { sample: Some(TableSample { method: SampleMethod::Bernoulli, percentage: 50 }), .. }, .. }
+        ));
+
+        let stmt = parse_sql("SELECT system FROM t;").unwrap();
+        assert!(matches!(stmt, Statement::Select { from: FromSource::Table { sample: None, .. }, .. }));
+
+        assert!(matches!(
+            parse_sql("SELECT id FROM t TABLESAMPLE SYSTEM (101);"),
+            Err(Error::ParserError(msg)) if msg == "TABLESAMPLE percentage must be at most 100, found 101"
+        ));
+        assert!(matches!(parse_sql("SELECT id FROM t TABLESAMPLE RANDOM (5);"), Err(Error::UnexpectedToken { .. })));
+    }
+
+    #[test]
+    fn test_parse_qualified_table_names() {
+        let table_name = |sql: &str| match crate::parse_with_dialect(sql, Dialect::Postgres).unwrap() {
+            Statement::Select { from: FromSource::Table { name, .. }, .. } => name,
+            other => panic!("Expected SELECT from a table, got {:?}", other),
+        };
+        assert_eq!(table_name("SELECT id FROM public.users;").parts, vec!["PUBLIC", "USERS"]);
+
+        // The parts are kept apart, so a dot inside a quoted part is not a separator
+        let name = table_name("SELECT id FROM mydb.\"Public\".users;");
+        assert_eq!(name.parts, vec!["MYDB", "Public", "USERS"]);
+        assert_eq!(name.to_string(), "MYDB.\"Public\".USERS");
+        let stmt = crate::parse_with_dialect("SELECT id FROM mydb.\"Public\".users;", Dialect::Postgres).unwrap();
+        assert_eq!(crate::parse_with_dialect(&format!("{};", stmt), Dialect::Postgres).unwrap(), stmt);
+        assert_ne!(table_name("SELECT id FROM \"a.b\".c;"), table_name("SELECT id FROM a.\"b.c\";"));
+        assert_eq!(table_name("SELECT id FROM \"a.b\".c;").to_string(), "\"a.b\".C");
+
+        let stmt = parse_sql("CREATE TABLE app.orders (user_id INT FOREIGN KEY (user_id) REFERENCES auth.users(id));").unwrap();
+        match stmt {
+            Statement::CreateTable { table_name, column_list, .. } => {
+                assert_eq!(table_name.to_string(), "APP.ORDERS");
+                assert!(matches!(
+                    &column_list[0].constraints[0],
+                    Constraint::ForeignKey { referenced_table, .. } if referenced_table.parts == ["AUTH", "USERS"]
+                ));
+            }
+            other => panic!("Expected CREATE TABLE, got {:?}", other),
+        }
+
+        assert!(matches!(
+            parse_sql("SELECT id FROM public.;"),
+            Err(Error::ParserError(msg)) if msg == "Expected table name, found Semicolon"
+        ));
+    }
+
+    #[test]
+    fn test_parse_row_in_subquery() {
+        use crate::ast::ident;
+
+        let scalar = parse_sql("SELECT id FROM t WHERE status IN (SELECT s FROM allowed);").unwrap();
+        assert!(matches!(
+            scalar,
+            Statement::Select { r#where: Some(Expression::InSubquery { ref expr, .. }), .. } if **expr == ident("STATUS")
+        ));
+
+        let stmt = parse_sql("SELECT id FROM t WHERE (a, b) IN (SELECT x, y FROM u);").unwrap();
+        match &stmt {
+            Statement::Select { r#where: Some(Expression::InSubquery { expr, subquery, negated: false }), .. } => {
+                assert_eq!(**expr, Expression::Row(vec![ident("A"), ident("B")]));
+                assert!(matches!(**subquery, Statement::Select { ref columns, .. } if columns.len() == 2));
+            }
+            other => panic!("Expected IN subquery, got {:?}", other),
+        }
+        assert_eq!(stmt.to_string(), "SELECT ID FROM T WHERE ((A, B) IN (SELECT X, Y FROM U))");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_parse_row_constructor() {
+        use crate::ast::{num, row};
+
+        let stmt = parse_sql("SELECT (1, 2) FROM t;").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::Select { ref columns, .. } if columns[0] == row(vec![num(1), num(2)])
+        ));
+
+        let grouped = parse_sql("SELECT (1) FROM t;").unwrap();
+        assert!(matches!(
+            grouped,
+            Statement::Select { ref columns, .. } if columns[0] == num(1)
+        ));
+
+        assert!(parse_sql("SELECT (1, 2,) FROM t;").is_err());
+    }
 
-    fn parse_sql(input: &str) -> Result<Statement, Error> {
-        let mut tokenizer = Tokenizer::new(input);
-        let tokens = tokenizer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens);
-        parser.parse()
+    #[test]
+    fn test_parse_row_comparison() {
+        use crate::ast::{ident, num, binary, row};
+
+        let stmt = parse_sql("SELECT id FROM t WHERE (a, b) = (1, 2);").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::Select { r#where: Some(ref expr), .. }
+                if *expr == binary(row(vec![ident("A"), ident("B")]), BinaryOperator::Equal, row(vec![num(1), num(2)]))
+        ));
+
+        let stmt = parse_sql("SELECT id FROM t WHERE (a, b) < (3, 4) AND c;").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::Select { r#where: Some(ref expr), .. } if *expr == binary(
+                binary(row(vec![ident("A"), ident("B")]), BinaryOperator::LessThan, row(vec![num(3), num(4)])),
+                BinaryOperator::And,
+                ident("C"),
+            )
+        ));
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
     }
 
     #[test]
-    fn test_parse_select_basic() {
-        let stmt = parse_sql("SELECT id, name FROM users;").unwrap();
+    fn test_parse_not_over_parenthesized_predicate() {
+        use crate::ast::{ident, num, string, unary};
+
+        let stmt = parse_sql("SELECT id FROM t WHERE NOT (a IN (1, 2)) AND NOT (b LIKE 'x%');").unwrap();
         match stmt {
-            Statement::Select { columns, from, r#where, orderby } => {
-                assert_eq!(columns.len(), 2);
-                assert_eq!(from, "USERS");
-                assert!(r#where.is_none());
-                assert!(orderby.is_empty());
+            Statement::Select { r#where: Some(Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand }), .. } => {
+                assert_eq!(*left_operand, unary(UnaryOperator::Not, Expression::InList {
+                    expr: Box::new(ident("A")),
+                    list: vec![num(1), num(2)],
+                    negated: false,
+                }));
+                assert_eq!(*right_operand, unary(UnaryOperator::Not, Expression::Like {
+                    expr: Box::new(ident("B")),
+                    pattern: Box::new(string("x%")),
+                    negated: false,
+                    escape: None,
+                }));
             }
-            _ => panic!("Expected Select statement"),
+            other => panic!("Expected AND of two negated predicates, got {:?}", other),
         }
+
+        let stmt = parse_sql("SELECT id FROM t WHERE NOT (a BETWEEN 1 AND 2);").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::Select { r#where: Some(Expression::UnaryOperation { operator: UnaryOperator::Not, ref operand }), .. }
+                if matches!(**operand, Expression::Between { negated: false, .. })
+        ));
     }
 
     #[test]
-    fn test_parse_select_where() {
-        let stmt = parse_sql("SELECT id FROM users WHERE age >= 18;").unwrap();
+    fn test_parse_not_in_subquery() {
+        use crate::ast::{ident, num, binary};
+
+        let stmt = parse_sql("SELECT id FROM users WHERE id NOT IN (SELECT banned_id FROM bans) AND age > 1;").unwrap();
         match stmt {
-            Statement::Select { columns: _, from: _, r#where, orderby: _ } => {
-                assert!(r#where.is_some());
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, binary(
+                    Expression::InSubquery {
+                        expr: Box::new(ident("ID")),
+                        subquery: Box::new(Statement::Select {
+                            columns: vec![ident("BANNED_ID")],
+                            from: FromSource::Table { name: "BANS".into(), only: false, sample: None },
+                            r#where: None,
+                            groupby: None,
+                            having: None,
+                            orderby: vec![],
+                            limit: None,
+                            offset: None,
+                        }),
+                        negated: true,
+                    },
+                    BinaryOperator::And,
+                    binary(ident("AGE"), BinaryOperator::GreaterThan, num(1)),
+                ));
             }
-            _ => panic!("Expected Select statement"),
+            _ => panic!("Expected Select statement with WHERE clause"),
         }
     }
 
     #[test]
-    fn test_parse_select_order_by() {
-        let stmt = parse_sql("SELECT id FROM users ORDER BY name ASC, age DESC;").unwrap();
+    fn test_parse_quantified_comparison() {
+        use crate::ast::ident;
+
+        let stmt = parse_sql("SELECT name FROM staff WHERE salary > ALL (SELECT salary FROM managers);").unwrap();
         match stmt {
-            Statement::Select { columns: _, from: _, r#where: _, orderby } => {
-                assert_eq!(orderby.len(), 2);
+            Statement::Select { r#where: Some(Expression::Quantified { left, op, quantifier, subquery }), .. } => {
+                assert_eq!(*left, ident("SALARY"));
+                assert_eq!(op, BinaryOperator::GreaterThan);
+                assert_eq!(quantifier, Quantifier::All);
+                assert!(matches!(*subquery, Statement::Select { ref from, .. } if *from == FromSource::Table { name: "MANAGERS".into(), only: false, sample: None }));
             }
-            _ => panic!("Expected Select statement"),
+            other => panic!("Expected quantified comparison, got {:?}", other),
+        }
+
+        let stmt = parse_sql("SELECT name FROM staff WHERE dept = ANY (SELECT id FROM depts) OR id = 1;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { left_operand, operator: BinaryOperator::Or, .. }), .. } => {
+                assert!(matches!(*left_operand, Expression::Quantified { op: BinaryOperator::Equal, quantifier: Quantifier::Any, .. }));
+            }
+            other => panic!("Expected OR of a quantified comparison, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_parse_select_star() {
-        let stmt = parse_sql("SELECT * FROM users;").unwrap();
+    fn test_error_quantified_non_comparison() {
+        assert!(matches!(
+            parse_sql("SELECT name FROM staff WHERE salary + ANY (SELECT salary FROM managers) > 1;"),
+            Err(Error::ParserError(_))
+        ));
+        assert!(parse_sql("SELECT name FROM staff WHERE salary > ALL (1, 2);").is_err());
+    }
+
+    #[test]
+    fn test_parse_like_escape() {
+        use crate::ast::{ident, string};
+
+        let stmt = parse_sql(r"SELECT id FROM files WHERE path LIKE '100\%' ESCAPE '\';").unwrap();
+        assert!(matches!(
+            &stmt,
+            Statement::Select { r#where: Some(expr), .. } if *expr == Expression::Like {
+                expr: Box::new(ident("PATH")),
+                pattern: Box::new(string(r"100\%")),
+                negated: false,
+                escape: Some('\\'),
+            }
+        ));
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        // A quote as the escape character is doubled when printed
+        let stmt = parse_sql("SELECT id FROM files WHERE path LIKE 'a%' ESCAPE '''';").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT ID FROM FILES WHERE (PATH LIKE 'a%' ESCAPE '''')");
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+
+        // ESCAPE is only a keyword after a LIKE pattern
+        assert!(matches!(
+            parse_sql("SELECT escape FROM t WHERE escape LIKE 'a%';"),
+            Ok(Statement::Select { columns, .. }) if columns == vec![ident("ESCAPE")]
+        ));
+
+        let stmt = parse_sql("SELECT id FROM files WHERE path NOT LIKE 'a%';").unwrap();
+        assert!(matches!(stmt, Statement::Select { r#where: Some(Expression::Like { negated: true, escape: None, .. }), .. }));
+
+        assert!(matches!(
+            parse_sql("SELECT id FROM files WHERE path LIKE 'a!%' ESCAPE '!!';"),
+            Err(Error::ParserError(msg)) if msg == "ESCAPE must be a single character, found \"!!\""
+        ));
+        assert!(matches!(
+            parse_sql("SELECT id FROM files WHERE path LIKE 'a!%' ESCAPE 1;"),
+            Err(Error::ParserError(msg)) if msg.starts_with("Expected an escape character string after ESCAPE")
+        ));
+    }
+
+    #[test]
+    fn test_parse_negated_predicates() {
+        use crate::ast::{ident, num, string, binary, unary};
+
+        let stmt = parse_sql("SELECT id FROM users WHERE name NOT LIKE 'A%' AND age NOT BETWEEN 1 + 1 AND 10 AND id NOT IN (1);").unwrap();
         match stmt {
-            Statement::Select { columns, from: _, r#where: _, orderby: _ } => {
-                assert_eq!(columns.len(), 1);
-                assert!(matches!(&columns[0], Expression::Identifier(s) if s == "*"));
+            Statement::Select { r#where: Some(expr), .. } => {
+                assert_eq!(expr, binary(
+                    binary(
+                        Expression::Like { expr: Box::new(ident("NAME")), pattern: Box::new(string("A%")), negated: true, escape: None },
+                        BinaryOperator::And,
+                        Expression::Between {
+                            expr: Box::new(ident("AGE")),
+                            low: Box::new(binary(num(1), BinaryOperator::Plus, num(1))),
+                            high: Box::new(num(10)),
+                            negated: true,
+                        },
+                    ),
+                    BinaryOperator::And,
+                    Expression::InList { expr: Box::new(ident("ID")), list: vec![num(1)], negated: true },
+                ));
             }
-            _ => panic!("Expected Select statement"),
+            _ => panic!("Expected Select statement with WHERE clause"),
+        }
+
+        let stmt = parse_sql("SELECT id FROM users WHERE name LIKE 'A%' OR age BETWEEN 1 AND 2;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(Expression::BinaryOperation { left_operand, right_operand, .. }), .. } => {
+                assert!(matches!(*left_operand, Expression::Like { negated: false, .. }));
+                assert!(matches!(*right_operand, Expression::Between { negated: false, .. }));
+            }
+            other => panic!("Expected OR of LIKE and BETWEEN, got {:?}", other),
+        }
+
+        // A NOT that does not start a predicate is still a prefix operator
+        let stmt = parse_sql("SELECT id FROM users WHERE NOT active;").unwrap();
+        match stmt {
+            Statement::Select { r#where: Some(expr), .. } => assert_eq!(expr, unary(UnaryOperator::Not, ident("ACTIVE"))),
+            _ => panic!("Expected Select statement with WHERE clause"),
         }
+
+        assert!(parse_sql("SELECT id FROM users WHERE age BETWEEN 1 OR 2;").is_err());
+        assert!(parse_sql("SELECT id FROM users WHERE age NOT 1;").is_err());
     }
 
     #[test]
-    fn test_parse_create_table_basic() {
-        let stmt = parse_sql("CREATE TABLE users (id INT, name VARCHAR(255));").unwrap();
+    fn test_error_in_list() {
+        assert!(parse_sql("SELECT id FROM users WHERE id IN ();").is_err());
+        assert!(parse_sql("SELECT id FROM users WHERE id IN (1, 2,);").is_err());
+        assert!(parse_sql("SELECT id FROM users WHERE id NOT IN 1;").is_err());
+    }
+
+    #[test]
+    fn test_error_is_without_null() {
+        assert!(matches!(
+            parse_sql("SELECT id FROM users WHERE email IS 5;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT id FROM users WHERE email IS NOT 5;"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_nesting_too_deep() {
+        let query = format!("SELECT {}1{} FROM t;", "(".repeat(1000), ")".repeat(1000));
+        assert!(matches!(
+            parse_sql(&query),
+            Err(Error::ParserError(msg)) if msg == "expression nesting too deep"
+        ));
+
+        // Spaced out, since `--` starts a comment
+        let query = format!("SELECT {}1 FROM t;", "- ".repeat(1000));
+        assert!(matches!(
+            parse_sql(&query),
+            Err(Error::ParserError(msg)) if msg == "expression nesting too deep"
+        ));
+    }
+
+    #[test]
+    fn test_strict_reserved_word_table_name() {
+        let strict = |sql: &str| {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            Parser::new(tokens).with_strict(true).parse()
+        };
+        assert!(matches!(
+            strict("CREATE TABLE order (id INT);"),
+            Err(Error::ParserError(msg)) if msg == "'ORDER' is a reserved word and cannot be used as an identifier"
+        ));
+        assert!(matches!(
+            strict("SELECT id FROM table;"),
+            Err(Error::ParserError(msg)) if msg == "'TABLE' is a reserved word and cannot be used as an identifier"
+        ));
+        assert!(matches!(
+            strict("CREATE TABLE t (from INT);"),
+            Err(Error::ParserError(msg)) if msg.starts_with("'FROM' is a reserved word")
+        ));
+        assert!(strict("SELECT id FROM users;").is_ok());
+
+        let tokens = Tokenizer::new_with_dialect("SELECT id FROM order;", Dialect::MySql).tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).with_strict(true).with_dialect(Dialect::MySql).parse(),
+            Err(Error::ParserError(msg)) if msg == "'ORDER' is a reserved word; quote it as `ORDER` to use as an identifier"
+        ));
+        let tokens = Tokenizer::new_with_dialect("SELECT id FROM `order`;", Dialect::MySql).tokenize().unwrap();
+        assert!(Parser::new(tokens).with_strict(true).with_dialect(Dialect::MySql).parse().is_ok());
+
+        // Without strict mode the grammar reports the keyword as it always has
+        assert!(!matches!(
+            parse_sql("CREATE TABLE order (id INT);"),
+            Err(Error::ParserError(msg)) if msg.contains("reserved word")
+        ));
+    }
+
+    #[test]
+    fn test_non_reserved_keywords_as_identifiers() {
+        use crate::ast::{ident, num, binary, unary};
+
+        let stmt = parse_sql("SELECT key, index FROM t WHERE desc > 1 ORDER BY asc DESC;").unwrap();
+        assert_eq!(stmt, Statement::Select {
+            columns: vec![ident("KEY"), ident("INDEX")],
+            from: FromSource::Table { name: "T".into(), only: false, sample: None },
+            r#where: Some(binary(ident("DESC"), BinaryOperator::GreaterThan, num(1))),
+            groupby: None,
+            having: None,
+            orderby: vec![unary(UnaryOperator::Desc, ident("ASC"))],
+            limit: None,
+            offset: None,
+        });
+
+        let stmt = parse_sql("CREATE TABLE t (key INT PRIMARY KEY, on BOOL);").unwrap();
         match stmt {
-            Statement::CreateTable { table_name, column_list } => {
-                assert_eq!(table_name, "USERS");
-                assert_eq!(column_list.len(), 2);
-                assert!(matches!(column_list[0].column_type, DBType::Int));
-                assert!(matches!(column_list[1].column_type, DBType::Varchar(255)));
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_name, "KEY");
+                assert_eq!(column_list[1].column_name, "ON");
             }
             _ => panic!("Expected CreateTable statement"),
         }
+
+        assert!(parse_sql("SELECT from FROM t;").is_err());
+
+        // Reading a keyword as a name leaves the tokens as they were
+        let tokens = Tokenizer::new("CREATE INDEX key ON t (index);").tokenize().unwrap();
+        let mut parser = Parser::new(tokens.clone());
+        assert!(parser.parse().is_ok());
+        assert_eq!(parser.tokens, tokens);
     }
 
     #[test]
-    fn test_parse_create_table_constraints() {
-        let stmt = parse_sql("CREATE TABLE users (
-            id INT PRIMARY KEY,
-            email VARCHAR(255) NOT NULL,
-            age INT CHECK(age >= 18)
-        );").unwrap();
-        match stmt {
-            Statement::CreateTable { table_name: _, column_list } => {
-                assert_eq!(column_list.len(), 3);
-                assert!(column_list[0].constraints.contains(&Constraint::PrimaryKey));
-                assert!(column_list[1].constraints.contains(&Constraint::NotNull));
-                assert!(matches!(&column_list[2].constraints[0], Constraint::Check(_)));
+    fn test_interval_units_as_identifiers() {
+        use crate::ast::ident;
+
+        match parse_sql("SELECT year, day FROM t WHERE second > INTERVAL '1' SECOND;").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![ident("YEAR"), ident("DAY")]),
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        match parse_sql("CREATE TABLE events (year INT, month INT, hour INT, minute INT);").unwrap() {
+            Statement::CreateTable { column_list, .. } => {
+                let names: Vec<&str> = column_list.iter().map(|column| column.column_name.as_str()).collect();
+                assert_eq!(names, vec!["YEAR", "MONTH", "HOUR", "MINUTE"]);
             }
-            _ => panic!("Expected CreateTable statement"),
+            other => panic!("Expected CreateTable statement, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_parse_create_table_foreign_key() {
-        let stmt = parse_sql("CREATE TABLE orders (
-            id INT PRIMARY KEY,
-            user_id INT,
-            FOREIGN KEY (user_id) REFERENCES users(id)
-        );").unwrap();
-        match stmt {
-            Statement::CreateTable { table_name: _, column_list } => {
-                assert!(matches!(&column_list[1].constraints[0], 
-                    Constraint::ForeignKey { column, referenced_table, referenced_column }
-                    if column == "USER_ID" && referenced_table == "USERS" && referenced_column == "ID"
-                ));
+    fn test_row_limit_keywords_as_identifiers() {
+        use crate::ast::ident;
+
+        match parse_sql("SELECT next, rows FROM t ORDER BY row LIMIT 5 OFFSET 1;").unwrap() {
+            Statement::Select { columns, orderby, limit, offset, .. } => {
+                assert_eq!(columns, vec![ident("NEXT"), ident("ROWS")]);
+                assert_eq!(orderby, vec![ident("ROW")]);
+                assert_eq!((limit, offset), (Some(5), Some(1)));
             }
-            _ => panic!("Expected CreateTable statement"),
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        assert!(matches!(
+            parse_sql("SELECT a FROM only;"),
+            Ok(Statement::Select { from: FromSource::Table { name, only: false, .. }, .. }) if name.name() == "ONLY"
+        ));
+        assert!(matches!(
+            parse_sql("SELECT a FROM ONLY only WHERE fetch > 1;"),
+            Ok(Statement::Select { from: FromSource::Table { name, only: true, .. }, .. }) if name.name() == "ONLY"
+        ));
+        assert!(parse_sql("CREATE TABLE t (limit INT, offset INT);").is_ok());
+        assert!(parse_sql("SELECT a FROM t OFFSET 2 ROWS FETCH NEXT 3 ROWS ONLY;").is_ok());
+    }
+
+    #[test]
+    fn test_timestamp_keywords_as_identifiers() {
+        use crate::ast::ident;
+
+        match parse_sql("SELECT timestamp, current_date FROM t;").unwrap() {
+            // At the start of an expression CURRENT_DATE is still the current date, not a column
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![ident("TIMESTAMP"), Expression::Current(CurrentValue::Date)]),
+            other => panic!("Expected Select statement, got {:?}", other),
+        }
+        match parse_sql("CREATE TABLE t (timestamp TIMESTAMP, current_time INT);").unwrap() {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_name, "TIMESTAMP");
+                assert_eq!(column_list[0].column_type, DBType::Timestamp);
+                assert_eq!(column_list[1].column_name, "CURRENT_TIME");
+            }
+            other => panic!("Expected CreateTable statement, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_parse_expressions() {
-        let stmt = parse_sql("SELECT id * 2 + 3, (age - 18) / 2 FROM users;").unwrap();
-        match stmt {
-            Statement::Select { columns, from: _, r#where: _, orderby: _ } => {
-                assert_eq!(columns.len(), 2);
-                assert!(matches!(&columns[0], Expression::BinaryOperation { .. }));
-                assert!(matches!(&columns[1], Expression::BinaryOperation { .. }));
+    fn test_show_keywords_as_identifiers() {
+        match parse_sql("CREATE TABLE t (tables INT, show BOOL);").unwrap() {
+            Statement::CreateTable { column_list, .. } => {
+                assert_eq!(column_list[0].column_name, "TABLES");
+                assert_eq!(column_list[1].column_name, "SHOW");
             }
-            _ => panic!("Expected Select statement"),
+            other => panic!("Expected CreateTable statement, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT tables FROM show;").is_ok());
+        assert_eq!(parse_sql("SHOW TABLES;").unwrap(), Statement::ShowTables);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let query = format!("SELECT {}1{} FROM t;", "(".repeat(10), ")".repeat(10));
+        let tokens = Tokenizer::new(&query).tokenize().unwrap();
+        assert!(Parser::new(tokens.clone()).parse().is_ok());
+        assert!(Parser::new(tokens).with_max_depth(5).parse().is_err());
+    }
+
+    #[test]
+    fn test_max_depth_derived_tables() {
+        let nested = |levels: usize| {
+            format!("SELECT x FROM {}t{};", "(SELECT x FROM ".repeat(levels), ") AS s".repeat(levels))
+        };
+        assert!(parse_sql(&nested(10)).is_ok());
+        assert!(matches!(
+            parse_sql(&nested(100_000)),
+            Err(Error::ParserError(msg)) if msg.ends_with("nesting too deep")
+        ));
+        assert!(Parser::new(Tokenizer::new(&nested(3)).tokenize().unwrap()).with_max_depth(3).parse().is_err());
+    }
+
+    #[test]
+    fn test_number_literal_too_large() {
+        assert!(parse_sql("SELECT 9223372036854775807 FROM t;").is_ok());
+        assert!(matches!(
+            parse_sql("SELECT 9223372036854775808 FROM t;"),
+            Err(Error::ParserError(msg)) if msg == "Number 9223372036854775808 is too large"
+        ));
+
+        let stmt = parse_sql("SELECT -9223372036854775808, -9223372036854775807 FROM t;").unwrap();
+        match &stmt {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns[0], Expression::Number(i64::MIN));
+                assert_eq!(columns[1], Expression::UnaryOperation {
+                    operand: Box::new(Expression::Number(i64::MAX)),
+                    operator: UnaryOperator::Minus,
+                });
+            }
+            other => panic!("Expected SELECT, got {:?}", other),
         }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_prefix_sign_binds_to_its_operand() {
+        use crate::ast::{binary, ident, num, unary};
+
+        let stmt = parse_sql("SELECT -5 + 1 > 0, -9223372036854775808 + 1 > 0, -a * b ^ 2, +a IS NULL FROM t;").unwrap();
+        match &stmt {
+            Statement::Select { columns, .. } => {
+                let plus_one = |left| binary(binary(left, BinaryOperator::Plus, num(1)), BinaryOperator::GreaterThan, num(0));
+                assert_eq!(columns[0], plus_one(unary(UnaryOperator::Minus, num(5))));
+                assert_eq!(columns[1], plus_one(num(i64::MIN)));
+                assert_eq!(columns[2], binary(
+                    unary(UnaryOperator::Minus, ident("A")),
+                    BinaryOperator::Multiply,
+                    binary(ident("B"), BinaryOperator::Power, num(2)),
+                ));
+                assert_eq!(columns[3], Expression::IsNull {
+                    operand: Box::new(unary(UnaryOperator::Plus, ident("A"))),
+                    negated: false,
+                });
+            }
+            other => panic!("Expected SELECT, got {:?}", other),
+        }
+        assert_eq!(parse_sql(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_int_bounds() {
+        let tokens = Tokenizer::new("SELECT 0, 2147483647 FROM t WHERE id = 10;").tokenize().unwrap();
+        assert!(Parser::new(tokens).with_int_bounds(0, i32::MAX.into()).parse().is_ok());
+
+        let tokens = Tokenizer::new("SELECT id FROM t WHERE id = 2147483648;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).with_int_bounds(0, i32::MAX.into()).parse(),
+            Err(Error::ParserError(_))
+        ));
+
+        let tokens = Tokenizer::new("SELECT -2147483648, 2147483647 FROM t;").tokenize().unwrap();
+        assert!(Parser::new(tokens).with_int_bounds(i32::MIN.into(), i32::MAX.into()).parse().is_ok());
+
+        let tokens = Tokenizer::new("SELECT -2147483649 FROM t;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).with_int_bounds(i32::MIN.into(), i32::MAX.into()).parse(),
+            Err(Error::ParserError(msg)) if msg == "Number -2147483649 is outside the allowed range -2147483648..=2147483647"
+        ));
+
+        let tokens = Tokenizer::new("SELECT -1 FROM t;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).with_int_bounds(0, i64::MAX).parse(),
+            Err(Error::ParserError(_))
+        ));
+
+        let tokens = Tokenizer::new("SELECT -9223372036854775808 FROM t;").tokenize().unwrap();
+        assert!(Parser::new(tokens).with_int_bounds(i64::MIN, i64::MAX).parse().is_ok());
+
+        let tokens = Tokenizer::new("SELECT 5 FROM t;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).with_int_bounds(10, 20).parse(),
+            Err(Error::ParserError(_))
+        ));
+    }
+
+    #[test]
+    fn test_error_empty_input() {
+        assert!(matches!(parse_sql(""), Err(Error::EmptyInput)));
+        assert!(matches!(parse_sql("  \n\t "), Err(Error::EmptyInput)));
+        assert!(matches!(Parser::new(vec![]).parse(), Err(Error::EmptyInput)));
+        assert!(!matches!(parse_sql("SELECT"), Err(Error::EmptyInput)));
     }
 
     #[test]
@@ -767,9 +3766,12 @@ mod tests {
     fn test_parse_complex_select() {
         let stmt = parse_sql("SELECT id * 2 + 1, name FROM users WHERE age >= 18 AND (salary > 50000 OR department = 'IT') ORDER BY name DESC;").unwrap();
         match stmt {
-            Statement::Select { columns, from, r#where, orderby } => {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, limit, offset } => {
                 assert_eq!(columns.len(), 2);
-                assert_eq!(from, "USERS");
+                assert!(limit.is_none() && offset.is_none());
+                assert!(groupby.is_none());
+                assert!(having.is_none());
+                assert_eq!(from, FromSource::Table { name: "USERS".into(), only: false, sample: None });
                 assert!(r#where.is_some());
                 assert_eq!(orderby.len(), 1);
                 assert!(matches!(&orderby[0], Expression::UnaryOperation { .. }));
@@ -777,4 +3779,143 @@ mod tests {
             _ => panic!("Expected Select statement"),
         }
     }
+
+    #[test]
+    fn test_parse_quoted_reserved_table_names() {
+        use crate::parse_with_dialect;
+
+        // Delimited identifiers are plain identifier tokens, so every name site takes them
+        let stmt = parse_with_dialect("SELECT id FROM \"order\";", Dialect::Postgres).unwrap();
+        assert!(matches!(stmt, Statement::Select { from: FromSource::Table { ref name, .. }, .. } if name.name() == "order"));
+
+        let stmt = parse_with_dialect(
+            "CREATE TABLE \"select\" (id INT, FOREIGN KEY (id) REFERENCES \"order\"(id));",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        match stmt {
+            Statement::CreateTable { table_name, column_list, .. } => {
+                assert_eq!(table_name.name(), "select");
+                assert_eq!(column_list[0].constraints, vec![Constraint::ForeignKey {
+                    column: "ID".to_string(),
+                    referenced_table: "order".into(),
+                    referenced_column: "ID".to_string(),
+                }]);
+            }
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+
+        let stmt = parse_with_dialect("CREATE INDEX `from` ON `order` (`group`);", Dialect::MySql).unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::CreateIndex { ref index_name, ref table_name, .. } if index_name == "from" && table_name.name() == "order"
+        ));
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let budgeted = |sql: &str, budget: usize| {
+            let tokens = Tokenizer::new(sql).tokenize().unwrap();
+            Parser::new(tokens).with_token_budget(budget).parse()
+        };
+
+        let huge = format!("SELECT {} FROM t;", vec!["a"; 100].join(", "));
+        assert!(matches!(
+            budgeted(&huge, 20),
+            Err(Error::ParserError(msg)) if msg == "token budget exceeded"
+        ));
+        // SELECT, a, FROM, t and the semicolon
+        assert!(budgeted("SELECT a FROM t;", 5).is_ok());
+        assert!(budgeted("SELECT a FROM t;", 4).is_err());
+    }
+
+    #[test]
+    fn test_error_trailing_tokens_after_statement() {
+        match parse_sql("SELECT 1 FROM t; DROP TABLE x;") {
+            Err(Error::UnexpectedToken { expected, found, .. }) => {
+                assert_eq!(expected, "end of input");
+                assert!(found.contains("DROP"), "{}", found);
+            }
+            other => panic!("Expected an unexpected token error, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT 1 FROM t; -- done").is_ok());
+
+        let tokens = Tokenizer::new("SELECT 1 FROM t; SELECT 2 FROM t;").tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        assert!(parser.parse_next().is_ok());
+        assert!(parser.parse_next().is_ok());
+        assert!(parser.is_at_end());
+    }
+
+    #[test]
+    fn test_parse_skips_comment_tokens() {
+        let sql = "-- leading\nSELECT a, -- first\n b FROM t -- last\n;";
+        let tokens = Tokenizer::new(sql).with_keep_comments(true).tokenize().unwrap();
+        assert_eq!(Parser::new(tokens).parse().unwrap(), parse_sql(sql).unwrap());
+
+        let tokens = Tokenizer::new("-- nothing here").with_keep_comments(true).tokenize().unwrap();
+        assert!(matches!(Parser::new(tokens).parse(), Err(Error::EmptyInput)));
+    }
+
+    #[test]
+    fn test_parse_with_comments_round_trip() {
+        use crate::parse_with_comments;
+
+        let commented = parse_with_comments("-- note\n-- second line\nSELECT a FROM t; -- trailing").unwrap();
+        assert_eq!(commented.leading_comments, vec![" note", " second line"]);
+        assert_eq!(commented.statement, parse_sql("SELECT a FROM t;").unwrap());
+        assert_eq!(commented.to_string(), "-- note\n-- second line\nSELECT A FROM T");
+        assert_eq!(parse_with_comments(&format!("{};", commented)).unwrap(), commented);
+
+        assert!(parse_with_comments("SELECT a FROM t;").unwrap().leading_comments.is_empty());
+    }
+
+    #[test]
+    fn test_statements_iterator() {
+        use crate::Statements;
+
+        let statements: Vec<_> = Statements::new("SELECT a FROM t;\nSHOW TABLES;").collect();
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].as_ref().unwrap(), &parse_sql("SELECT a FROM t;").unwrap());
+        assert_eq!(statements[1].as_ref().unwrap(), &Statement::ShowTables);
+
+        let mut statements = Statements::new("BEGIN; SELECT FROM t; COMMIT;");
+        assert_eq!(statements.next().unwrap().unwrap(), Statement::Begin);
+        assert!(statements.next().unwrap().is_err());
+        assert!(statements.next().is_none());
+
+        assert!(Statements::new("-- nothing").next().is_none());
+        assert!(matches!(Statements::new("SELECT #").collect::<Vec<_>>().as_slice(), [Err(Error::LexerError(_))]));
+    }
+
+    #[test]
+    fn test_expect_identifier() {
+        let tokens = Tokenizer::new("users 1").tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(parser.expect_identifier("table name").unwrap(), "USERS");
+        assert!(matches!(
+            parser.expect_identifier("table name"),
+            Err(Error::ParserError(msg)) if msg == "Expected table name, found Number(1)"
+        ));
+
+        assert!(matches!(
+            parse_sql("CREATE TABLE t (id INT, FOREIGN KEY (id) REFERENCES 5(id));"),
+            Err(Error::InvalidForeignKey(msg)) if msg == "Expected table name, found Number(5)"
+        ));
+
+        // Tokens that stop where a FOREIGN KEY name belongs report the missing name
+        let unterminated = |sql: &str| {
+            let mut tokens = Tokenizer::new(sql).tokenize().unwrap();
+            tokens.pop();
+            Parser::new(tokens).parse()
+        };
+        for (sql, missing) in [
+            ("CREATE TABLE t (id INT, FOREIGN KEY (", "Missing column name"),
+            ("CREATE TABLE t (id INT, FOREIGN KEY (id) REFERENCES", "Missing referenced table name"),
+            ("CREATE TABLE t (id INT FOREIGN KEY (id) REFERENCES u(", "Missing referenced column name"),
+        ] {
+            assert!(matches!(unterminated(sql), Err(Error::InvalidForeignKey(msg)) if msg == missing), "{}", sql);
+        }
+    }
 }
+
@@ -0,0 +1,296 @@
+/// Table definitions that statements can be checked against
+/// The parser itself never needs a schema; this module is for callers that know their tables and want mistakes caught before a query is run.
+use std::collections::HashMap;
+use crate::error::Error;
+use crate::statement::{DBType, Expression, FromSource, Statement};
+
+/// The columns of every known table, in declaration order, with their types. Tables are keyed by their name as a statement prints it, so a schema-qualified table is `APP.ORDERS`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    tables: HashMap<String, Vec<(String, DBType)>>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a table with its columns, replacing any table of the same name
+    pub fn add_table(&mut self, table: &str, columns: Vec<(String, DBType)>) {
+        self.tables.insert(table.to_string(), columns);
+    }
+
+    /// Registers the table defined by a `CREATE TABLE` statement. Any other statement leaves the schema as it is.
+    pub fn apply(&mut self, stmt: &Statement) {
+        if let Statement::CreateTable { table_name, column_list, .. } = stmt {
+            let columns = column_list
+                .iter()
+                .map(|column| (column.column_name.clone(), column.column_type.clone()))
+                .collect();
+            self.add_table(&table_name.to_string(), columns);
+        }
+    }
+
+    /// Returns the columns of `table` in declaration order, or `None` if the table is unknown
+    pub fn table_columns(&self, table: &str) -> Option<&[(String, DBType)]> {
+        self.tables.get(table).map(Vec::as_slice)
+    }
+
+    /// Returns the type of `column` in `table`, or `None` if either is unknown
+    pub fn column_type(&self, table: &str, column: &str) -> Option<DBType> {
+        self.tables
+            .get(table)?
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, column_type)| column_type.clone())
+    }
+}
+
+/// The broad kind of value an expression produces, which is all the type check needs to compare
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    Number,
+    Text,
+    Boolean,
+}
+
+impl ValueKind {
+    /// Returns the kind of the values a column holds. Timestamps have none, since they are routinely compared with string literals.
+    fn of_type(column_type: &DBType) -> Option<Self> {
+        match column_type {
+            DBType::SmallInt | DBType::Int | DBType::BigInt => Some(ValueKind::Number),
+            DBType::Varchar(_) => Some(ValueKind::Text),
+            DBType::Bool => Some(ValueKind::Boolean),
+            DBType::Timestamp => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ValueKind::Number => "a number",
+            ValueKind::Text => "a string",
+            ValueKind::Boolean => "a boolean",
+        }
+    }
+}
+
+impl Statement {
+    /// Checks a `SELECT` on a known table for comparisons between values that can never be equal, such as an `INT` column and a string literal. This is not full type checking: anything whose type is not obvious, like a function call or an unknown column, is let through.
+    ///
+    /// # Arguments
+    /// * `schema` - The tables the statement is checked against
+    ///
+    /// # Returns
+    /// `Ok(())`, or `Error::TypeMismatch` describing the first mismatch found
+    pub fn type_check(&self, schema: &Schema) -> Result<(), Error> {
+        match self {
+            Statement::Select { from: FromSource::Table { name, .. }, .. } => {
+                let name = name.to_string();
+                for expr in self.child_expressions() {
+                    check_expression(expr, &name, schema)?;
+                }
+                Ok(())
+            }
+            Statement::Select { from: FromSource::Derived { query, .. }, .. } => query.type_check(schema),
+            Statement::With { ctes, body } => {
+                for (_, query) in ctes {
+                    query.type_check(schema)?;
+                }
+                body.type_check(schema)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a copy of the statement where the `*` of every `SELECT` on a known table is replaced by the table's columns, in declaration order. A `*` over a derived table is left in place, but the derived query itself is expanded.
+    ///
+    /// # Arguments
+    /// * `schema` - The tables the columns are taken from
+    ///
+    /// # Returns
+    /// The expanded statement, or `Error::UnknownTable` if a `*` selects from a table the schema does not have
+    pub fn expand_wildcards(&self, schema: &Schema) -> Result<Statement, Error> {
+        match self {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, limit, offset } => {
+                let from = match from {
+                    FromSource::Derived { query, alias } => FromSource::Derived {
+                        query: Box::new(query.expand_wildcards(schema)?),
+                        alias: alias.clone(),
+                    },
+                    table => table.clone(),
+                };
+
+                let mut expanded = Vec::new();
+                for column in columns {
+                    match (column, &from) {
+                        (Expression::Identifier(name), FromSource::Table { name: table, .. }) if name == "*" => {
+                            let table = table.to_string();
+                            let table_columns = schema
+                                .table_columns(&table)
+                                .ok_or(Error::UnknownTable(table))?;
+                            expanded.extend(table_columns.iter().map(|(name, _)| Expression::Identifier(name.clone())));
+                        }
+                        (column, _) => expanded.push(column.clone()),
+                    }
+                }
+
+                Ok(Statement::Select {
+                    columns: expanded,
+                    from,
+                    r#where: r#where.clone(),
+                    groupby: groupby.clone(),
+                    having: having.clone(),
+                    orderby: orderby.clone(),
+                    limit: *limit,
+                    offset: *offset,
+                })
+            }
+            Statement::With { ctes, body } => {
+                let ctes = ctes
+                    .iter()
+                    .map(|(name, query)| Ok((name.clone(), query.expand_wildcards(schema)?)))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(Statement::With {
+                    ctes,
+                    body: Box::new(body.expand_wildcards(schema)?),
+                })
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+/// Walks an expression, checking every comparison and `IN` list in it
+fn check_expression(expr: &Expression, table: &str, schema: &Schema) -> Result<(), Error> {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            check_expression(left_operand, table, schema)?;
+            check_expression(right_operand, table, schema)?;
+            if operator.is_comparison() {
+                check_comparable(left_operand, right_operand, table, schema)?;
+            }
+            Ok(())
+        }
+        Expression::InList { expr, list, .. } => {
+            check_expression(expr, table, schema)?;
+            for item in list {
+                check_expression(item, table, schema)?;
+                check_comparable(expr, item, table, schema)?;
+            }
+            Ok(())
+        }
+        Expression::Between { expr, low, high, .. } => {
+            for operand in [expr, low, high] {
+                check_expression(operand, table, schema)?;
+            }
+            check_comparable(expr, low, table, schema)?;
+            check_comparable(expr, high, table, schema)
+        }
+        Expression::Like { expr: left, pattern: right, .. } | Expression::DistinctFrom { left, right, .. } => {
+            check_expression(left, table, schema)?;
+            check_expression(right, table, schema)?;
+            check_comparable(left, right, table, schema)
+        }
+        Expression::UnaryOperation { operand, .. }
+        | Expression::IsNull { operand, .. }
+        | Expression::IsUnknown { operand, .. }
+        | Expression::Alias { expr: operand, .. }
+        | Expression::InSubquery { expr: operand, .. }
+        | Expression::Quantified { left: operand, .. } => check_expression(operand, table, schema),
+        Expression::FunctionCall { args, .. } | Expression::Row(args) | Expression::And(args) | Expression::Or(args) => {
+            args.iter().try_for_each(|arg| check_expression(arg, table, schema))
+        }
+        Expression::Case { operand, branches, else_result } => {
+            let branch_parts = branches.iter().flat_map(|(condition, result)| [condition, result]);
+            operand
+                .as_deref()
+                .into_iter()
+                .chain(branch_parts)
+                .chain(else_result.as_deref())
+                .try_for_each(|part| check_expression(part, table, schema))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_comparable(left: &Expression, right: &Expression, table: &str, schema: &Schema) -> Result<(), Error> {
+    match (value_kind(left, table, schema), value_kind(right, table, schema)) {
+        (Some(left_kind), Some(right_kind)) if left_kind != right_kind => Err(Error::TypeMismatch(format!(
+            "cannot compare {} ({}) with {} ({})",
+            left,
+            left_kind.name(),
+            right,
+            right_kind.name()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the kind of value an expression produces, when that is obvious from the expression alone or from the schema
+fn value_kind(expr: &Expression, table: &str, schema: &Schema) -> Option<ValueKind> {
+    match expr {
+        Expression::Number(_) => Some(ValueKind::Number),
+        Expression::String(_) => Some(ValueKind::Text),
+        Expression::Bool(_) => Some(ValueKind::Boolean),
+        Expression::Identifier(name) => schema.column_type(table, name).and_then(|column_type| ValueKind::of_type(&column_type)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn users() -> Schema {
+        let mut schema = Schema::new();
+        schema.add_table("USERS", vec![
+            ("AGE".to_string(), DBType::Int),
+            ("NAME".to_string(), DBType::Varchar(50)),
+        ]);
+        schema
+    }
+
+    #[test]
+    fn test_type_check_int_against_string() {
+        let stmt = parse("SELECT name FROM users WHERE age = 'twenty';").unwrap();
+        assert!(matches!(stmt.type_check(&users()), Err(Error::TypeMismatch(_))));
+
+        let stmt = parse("SELECT name FROM users WHERE age IN (1, 'two');").unwrap();
+        assert!(matches!(stmt.type_check(&users()), Err(Error::TypeMismatch(_))));
+    }
+
+    #[test]
+    fn test_type_check_accepts_matching_types() {
+        let stmt = parse("SELECT name FROM users WHERE age >= 20 AND name = 'Ann' AND unknown = 'x';").unwrap();
+        assert!(stmt.type_check(&users()).is_ok());
+    }
+
+    #[test]
+    fn test_apply_create_table() {
+        let mut schema = Schema::new();
+        schema.apply(&parse("CREATE TABLE users (id INT PRIMARY KEY, name VARCHAR(20), active BOOL);").unwrap());
+        schema.apply(&parse("SELECT id FROM users;").unwrap());
+
+        assert_eq!(schema.column_type("USERS", "ID"), Some(DBType::Int));
+        assert_eq!(schema.column_type("USERS", "NAME"), Some(DBType::Varchar(20)));
+        assert_eq!(schema.column_type("USERS", "ACTIVE"), Some(DBType::Bool));
+        assert_eq!(schema.column_type("USERS", "EMAIL"), None);
+        assert_eq!(schema.column_type("ORDERS", "ID"), None);
+    }
+
+    #[test]
+    fn test_expand_wildcards() {
+        let mut schema = Schema::new();
+        schema.apply(&parse("CREATE TABLE users (id INT, name VARCHAR(20), active BOOL);").unwrap());
+
+        let stmt = parse("SELECT * FROM users WHERE id > 1;").unwrap();
+        assert_eq!(stmt.expand_wildcards(&schema).unwrap(), parse("SELECT id, name, active FROM users WHERE id > 1;").unwrap());
+
+        let stmt = parse("SELECT * FROM orders;").unwrap();
+        assert!(matches!(stmt.expand_wildcards(&schema), Err(Error::UnknownTable(table)) if table == "ORDERS"));
+
+        let stmt = parse("SELECT id FROM users;").unwrap();
+        assert_eq!(stmt.expand_wildcards(&schema).unwrap(), stmt);
+    }
+}
@@ -1,16 +1,53 @@
 use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+use crate::error::Error;
+use crate::token::lookup_keyword;
+
+/// The aggregate functions `Statement::validate_aggregates` looks for
+const AGGREGATE_FUNCTIONS: [&str; 5] = ["COUNT", "SUM", "AVG", "MIN", "MAX"];
 
 /// The main entity of the whole parser. `Statement` is implemented as an enumeration because adding functionality is as easy as adding an enumeration constant and implementing functionality for that enumeration constant (implementation in the database command interpreter, which is not a part of this project). Parsing any correct `SELECT` or `CREATE`  (or `UPDATE`, `INSERT INTO`, ... hypothetically) statement should be turned into an instance of this enumeration. Ultimately, your main parser function (something like `build_statement(query: &str) -> Statement`) should return this enumeration.
 ///
-/// The `SELECT` statement has four components:
+/// The `SELECT` statement has eight components:
 /// 1. `columns` – A vector of columns from the selected table that the database should return.
-/// 2. `from` – A simple string, containing a table that is being queried (we aren't doing joins because they complicate stuff too much for this project).
+/// 2. `from` – The source of the rows, see `FromSource`: either the name of a table or a parenthesized `SELECT` with an alias (we aren't doing joins because they complicate stuff too much for this project).
 /// 3. `where` – A single expression that is the actual filter for the database query. It is wrapped in an `Option` because not every `SELECT` query contains a filter. The actual name is `r#where` because in Rust, `where` is a reserved keyword, and the prefix `r#` means: interpret this token as a raw string, do not check for keyword matches.
-/// 4. `orderby` – A vector of expressions that define how should the data be ordered. A vector is needed because the data can be ordered by the first column, and then all data that has the same first column can be ordered by the second column, ... Also, the data can be ordered not simply by columns, but by complex expressions as well.
+/// 4. `groupby` – The optional `GROUP BY` clause, see `GroupBy`.
+/// 5. `having` – The optional `HAVING` filter, applied to the groups rather than the rows, so unlike `where` it may use aggregate functions.
+/// 6. `orderby` – A vector of expressions that define how should the data be ordered. A vector is needed because the data can be ordered by the first column, and then all data that has the same first column can be ordered by the second column, ... Also, the data can be ordered not simply by columns, but by complex expressions as well.
+/// 7. `limit` – The most rows to return, written `LIMIT count` or `FETCH NEXT count ROWS ONLY`. `None` returns every row.
+/// 8. `offset` – How many rows to skip before the first one returned, written `OFFSET skip`.
 ///
-/// The `CREATE TABLE` statement has two components:
-/// 1. `table_name` – A simple string, the name of the table.
+/// The `CREATE TABLE` statement has three components:
+/// 1. `table_name` – The name of the table, see `QualifiedName`.
 /// 2. `column_list` – A vector of table column types, where each table column contains the definition of one column.
+/// 3. `constraints` – A vector of table-level constraints that are not tied to a single column, like `CHECK (low < high)`.
+///
+/// The `CREATE TABLE ... AS` statement has two components:
+/// 1. `table_name` – The name of the new table.
+/// 2. `query` – The `SELECT` whose result fills the table and decides its columns.
+///
+/// The `CREATE VIEW` statement has three components:
+/// 1. `view_name` – The name of the view.
+/// 2. `columns` – Names for the view's columns, one per item of the select list. It is empty when the statement gives no names, in which case the columns are named after the select list.
+/// 3. `query` – The `SELECT` that defines the view.
+///
+/// The `WITH` statement has two components:
+/// 1. `ctes` – A vector of common table expressions, each a name and the `SELECT` that defines it.
+/// 2. `body` – The main statement, which can refer to the common table expressions by name.
+///
+/// The `VALUES` statement is a list of rows, each a list of expressions. Every row has the same number of values.
+///
+/// The `INSERT INTO` statement has three components:
+/// 1. `table_name` – The table the rows are added to.
+/// 2. `columns` – The columns the values are given for, in order. It is empty when the statement gives no column list, in which case the values are for every column of the table.
+/// 3. `rows` – The rows to insert, as in a `VALUES` statement. Every row has one value per column.
+///
+/// The `SET` statement assigns the value of an expression to a user variable, `SET @name = value`. The `name` is kept without the `@`.
+///
+/// The transaction control statements `BEGIN`, `COMMIT` and `ROLLBACK` have no components.
+///
+/// The metadata statements are `SHOW TABLES`, which has no components, and `DESCRIBE`, which has a single `table_name`.
 ///
 /// Examples:
 ///
@@ -19,15 +56,19 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT name, surname FROM users;
 /// ```
 /// is a `SELECT` statement that,  when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::Select {
 ///     columns: vec![
-/// 		Expression::Identifier("name".to_string()),
-/// 		Expression:Identifier("surname".to_string())
-/// 	],
-///     from: "users".to_string(),
+///         Expression::Identifier("name".to_string()),
+///         Expression:Identifier("surname".to_string())
+///     ],
+///     from: FromSource::Table { name: QualifiedName::from("users"), only: false, sample: None },
 ///     r#where: None,
-///     orderby: vec![]
+///     groupby: None,
+///     having: None,
+///     orderby: vec![],
+///     limit: None,
+///     offset: None
 /// }
 /// ```
 /// ---
@@ -35,7 +76,7 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT age * 5, 'this is a string' FROM users;
 /// ```
 /// is a `SELECT` statement that,  when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::Select {
 ///     columns: vec![
 ///         Expression::BinaryOperation {
@@ -45,9 +86,13 @@ use std::fmt::{Debug, Display, Formatter};
 ///         },
 ///         Expression::String("this is a string".to_string()),
 ///     ],
-///     from: "users".to_string(),
+///     from: FromSource::Table { name: QualifiedName::from("users"), only: false, sample: None },
 ///     r#where: None,
-///     orderby: vec![]
+///     groupby: None,
+///     having: None,
+///     orderby: vec![],
+///     limit: None,
+///     offset: None
 /// }
 /// ```
 /// ---
@@ -55,13 +100,13 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT name, surname FROM users WHERE name = \"Voldemort\" AND surname = 'Riddle';
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::Select {
 ///     columns: [
 ///         Expression::Identifier("name".to_string()),
 ///         Expression::Identifier("surname".to_string()),
 ///     ],
-///     from: "users".to_string(),
+///     from: FromSource::Table { name: QualifiedName::from("users"), only: false, sample: None },
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::BinaryOperation {
@@ -77,7 +122,11 @@ use std::fmt::{Debug, Display, Formatter};
 ///             }),
 ///         },
 ///     ),
-///     orderby: vec![]
+///     groupby: None,
+///     having: None,
+///     orderby: vec![],
+///     limit: None,
+///     offset: None
 /// }
 /// ```
 ///  ---
@@ -85,14 +134,16 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT id, salary FROM users ORDER BY salary - 2 * 10 ASC, id DESC;
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::Select {
 ///     columns: vec![
 ///         Expression::Identifier("id".to_string()),
 ///         Expression::Identifier("salary".to_string()),
 ///     ],
-///     from: "users".to_string(),
+///     from: FromSource::Table { name: QualifiedName::from("users"), only: false, sample: None },
 ///     r#where: None,
+///     groupby: None,
+///     having: None,
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
 ///             operand: Box::new(Expression::BinaryOperation {
@@ -111,6 +162,8 @@ use std::fmt::{Debug, Display, Formatter};
 ///             operator: UnaryOperator::Desc,
 ///         },
 ///     ],
+///     limit: None,
+///     offset: None
 /// }
 /// ```
 ///  ---
@@ -118,12 +171,12 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT id FROM registered_users WHERE password_encryption = TRUE ORDER BY id DESC;
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::Select {
 ///     columns: vec![
 ///         Expression::Identifier("id".to_string())
 ///     ],
-///     from: "registered_users".to_string(),
+///     from: FromSource::Table { name: QualifiedName::from("registered_users"), only: false, sample: None },
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::Identifier("password_encryption".to_string())),
@@ -131,26 +184,30 @@ use std::fmt::{Debug, Display, Formatter};
 ///             right_operand: Box::new(Expression::Bool(true))
 ///         }
 ///     ),
+///     groupby: None,
+///     having: None,
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
 ///             operand: Box::new(Expression::Identifier("id".to_string())),
 ///             operator: UnaryOperator::Desc
 ///         }
-///     ]
+///     ],
+///     limit: None,
+///     offset: None
 /// }
 /// ```
 /// ---
 /// ```sql
 /// CREATE TABLE simple_table(
-/// 	int_col INT,
-/// 	string_col VARCHAR(255),
-/// 	bool_col BOOL
+///     int_col INT,
+///     string_col VARCHAR(255),
+///     bool_col BOOL
 /// );
 /// ```
 /// is a  `CREATE TABLE` statement that, when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::CreateTable {
-///     table_name: "simple_table".to_string(),
+///     table_name: QualifiedName::from("simple_table"),
 ///     column_list: vec![
 ///         TableColumn {
 ///             column_name: "int_col".to_string(),
@@ -167,20 +224,21 @@ use std::fmt::{Debug, Display, Formatter};
 ///             column_type: DBType::Bool,
 ///             constraints: vec![],
 ///         },
-///     ]
+///     ],
+///     constraints: vec![],
 /// }
 /// ```
 /// ---
 /// ```sql
 /// CREATE TABLE complex_table(
-/// 	id INT PRIMARY KEY,
-/// 	email VARCHAR(255) NOT NULL,
-/// 	is_junior BOOL,
-/// 	age INT CHECK(age >= 18) CHECK(age <= 65)
+///     id INT PRIMARY KEY,
+///     email VARCHAR(255) NOT NULL,
+///     is_junior BOOL,
+///     age INT CHECK(age >= 18) CHECK(age <= 65)
 /// );
 /// ```
 /// is a  `CREATE TABLE` statement that, when parsed, looks like this:
-/// ```rust
+/// ```rust,ignore
 /// Statement::CreateTable {
 ///     table_name: Expression::Identifier("complex_table".to_string()),
 ///     column_list: vec![
@@ -220,6 +278,7 @@ use std::fmt::{Debug, Display, Formatter};
 ///             ],
 ///         },
 ///     ],
+///     constraints: vec![],
 /// }
 /// ```
 /// ---
@@ -233,44 +292,122 @@ use std::fmt::{Debug, Display, Formatter};
 /// CREATE TABLE work_hours(num_hours INT)
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it (no semicolon at the end).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Statement {
     Select {
         columns: Vec<Expression>,
-        from: String,
+        from: FromSource,
         r#where: Option<Expression>,
+        groupby: Option<GroupBy>,
+        having: Option<Expression>,
         orderby: Vec<Expression>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     },
     CreateTable {
-        table_name: String,
+        table_name: QualifiedName,
         column_list: Vec<TableColumn>,
+        constraints: Vec<Constraint>,
+    },
+    CreateTableAs {
+        table_name: QualifiedName,
+        query: Box<Statement>,
     },
     CreateIndex {
         is_unique: bool,
         index_name: String,
-        table_name: String,
-        column_name: String,
-    }
+        table_name: QualifiedName,
+        columns: Vec<(String, OrderDirection)>,
+        predicate: Option<Expression>,
+    },
+    CreateView {
+        view_name: QualifiedName,
+        columns: Vec<String>,
+        query: Box<Statement>,
+    },
+    With {
+        ctes: Vec<(String, Statement)>,
+        body: Box<Statement>,
+    },
+    Values(Vec<Vec<Expression>>),
+    SetVariable {
+        name: String,
+        value: Expression,
+    },
+    Insert {
+        table_name: QualifiedName,
+        columns: Vec<String>,
+        rows: Vec<Vec<Expression>>,
+    },
+    ShowTables,
+    Describe {
+        table_name: QualifiedName,
+    },
+    Begin,
+    Commit,
+    Rollback,
+}
+
+/// A statement together with the `--` comments written directly before it, for tools that reformat SQL without losing the comments. Each comment is the text after its dashes. It is printed as the comments, one per line, followed by the statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommentedStatement {
+    pub leading_comments: Vec<String>,
+    pub statement: Statement,
+}
+
+/// The kind of a `Statement`, without any of its contents. Useful for dispatching on or counting statements without matching on every field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Select,
+    CreateTable,
+    CreateTableAs,
+    CreateIndex,
+    CreateView,
+    With,
+    Values,
+    SetVariable,
+    Insert,
+    ShowTables,
+    Describe,
+    Begin,
+    Commit,
+    Rollback,
 }
 
 /// The main entity of the expression parser. The Expression enum is structured like this, where an expression can contain another expression. This naturally allows us to represent complex expressions as trees. `Box<T>` smart pointers are used on unary and binary types of expressions because the compiler needs to know the size of the enum at compile time which is impossible when an enum contains itself (infinite size).
 ///
 /// An expression can be:
 /// * complex - a number of other expressions (tree-like structure, unary and binary operations)
-/// * a single number
+/// * a single number, a signed 64-bit integer. The parser reads `-5` as a minus over `5`, see `Expression::simplify_negation`, except for `-9223372036854775808`, which only fits once negated and is read as one number. A negative number prints in parentheses, `(-5)`
 /// * a single identifier (like a variable 'x')
 /// * a single string (when doing parsing of WHERE statements that do operations with strings, strings must be in matching quotes – either `""` or `''`)
 /// * a boolean (only true or false)
+/// * the `UNKNOWN` truth value, the third value of SQL's three-valued logic
+/// * an interval literal (`INTERVAL '7' DAY`), used for date arithmetic
+/// * the current date or time (`CURRENT_DATE`, `CURRENT_TIME` or `CURRENT_TIMESTAMP`), written without parentheses
+/// * a function call (like `COALESCE(a, 0)`), with a name and a list of argument expressions
+/// * a row constructor (`(a, b)`), a parenthesized list of two or more values compared or tested for membership as a whole
+/// * a bind parameter (`?` or `?1`), which is filled in with a value when a prepared statement is executed
+/// * a named bind parameter (`:name` or `$name`), stored together with its prefix
+/// * a user variable (`@name`), stored without the `@`
+/// * a null test (`x IS NULL` or `x IS NOT NULL`)
+/// * an n-ary conjunction or disjunction (`And`, `Or`); the parser never produces these, they come from `optimize::flatten_boolean`
+/// * an unknown test (`x IS UNKNOWN` or `x IS NOT UNKNOWN`), which asks whether a condition evaluated to neither true nor false
+/// * a pattern match (`name LIKE 'A%'` or `name NOT LIKE 'A%'`)
+/// * a range test (`age BETWEEN 18 AND 65` or `age NOT BETWEEN 18 AND 65`), where both bounds are included
+/// * a membership test against a list of values (`x IN (1, 2, 3)`) or against the rows of a subquery (`x NOT IN (SELECT y FROM t)`)
+/// * a select-list item with an alias (`price * 2 AS doubled`), which names the resulting column
+/// * a quantified comparison (`x > ALL (SELECT y FROM t)` or `x = ANY (SELECT y FROM t)`), which compares a value with every row of a subquery
 ///
 /// Examples:
 ///
 /// ---
-/// ```
+/// ```text
 /// (13 + 7) - 4
 /// ```
 /// is an expression that contains two expressions:
 /// 1. `(13 + 7)` which is
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::Number(13)),
 ///     operator: BinaryOperator::Plus,
@@ -278,11 +415,11 @@ pub enum Statement {
 /// }
 /// ```
 /// 2. `4` which is
-/// ```rust
+/// ```rust,ignore
 /// Expression::Number(4)
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::Number(13)),
@@ -294,12 +431,12 @@ pub enum Statement {
 /// }
 /// ```
 /// ---
-/// ```
+/// ```text
 /// (5 - x) < (4 + y) OR name = "Donna"
 /// ```
 /// is an expression that contains five (three small and two combining) expressions:
 /// 1. `(5 - x)` which is
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::Number(5)),
 ///     operator: BinaryOperator::Minus,
@@ -307,7 +444,7 @@ pub enum Statement {
 /// }
 /// ```
 /// 2. `(4 - y)` which is
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::Number(4)),
 ///     operator: BinaryOperator::Plus,
@@ -315,7 +452,7 @@ pub enum Statement {
 /// }
 /// ```
 /// 3. `name = "Donna"` which is
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::Identifier("name".to_string())),
 ///     operator: BinaryOperator::Equal,
@@ -323,7 +460,7 @@ pub enum Statement {
 /// }
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::BinaryOperation {
@@ -347,11 +484,11 @@ pub enum Statement {
 /// }
 /// ```
 /// ---
-/// ```
+/// ```text
 /// NOT some_boolean = TRUE
 /// ```
 /// should look like this:
-/// ```rust
+/// ```rust,ignore
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::UnaryOperation {
 ///         left_operand: Box::new(Expression::Identifier("some_boolean".to_string())),
@@ -362,12 +499,12 @@ pub enum Statement {
 /// }
 /// ```
 /// ---
-/// ```
+/// ```text
 /// 5 * 3 - 4 + c / (13 -)
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it.
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expression {
     BinaryOperation {
         left_operand: Box<Expression>,
@@ -378,51 +515,246 @@ pub enum Expression {
         operand: Box<Expression>,
         operator: UnaryOperator,
     },
-    Number(u64),
+    Number(i64),
     Bool(bool),
+    Unknown,
     Identifier(String),
     String(String),
+    Interval {
+        value: String,
+        unit: IntervalUnit,
+    },
+    Current(CurrentValue),
+    FunctionCall {
+        name: String,
+        args: Vec<Expression>,
+    },
+    Row(Vec<Expression>),
+    Parameter(Option<u32>),
+    NamedParameter(String),
+    Variable(String),
+    IsNull {
+        operand: Box<Expression>,
+        negated: bool,
+    },
+    IsUnknown {
+        operand: Box<Expression>,
+        negated: bool,
+    },
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+    InList {
+        expr: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+    },
+    InSubquery {
+        expr: Box<Expression>,
+        subquery: Box<Statement>,
+        negated: bool,
+    },
+    Quantified {
+        left: Box<Expression>,
+        op: BinaryOperator,
+        quantifier: Quantifier,
+        subquery: Box<Statement>,
+    },
+    Alias {
+        expr: Box<Expression>,
+        alias: String,
+    },
+    Like {
+        expr: Box<Expression>,
+        pattern: Box<Expression>,
+        negated: bool,
+        escape: Option<char>,
+    },
+    DistinctFrom {
+        left: Box<Expression>,
+        right: Box<Expression>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expression>,
+        low: Box<Expression>,
+        high: Box<Expression>,
+        negated: bool,
+    },
+    Case {
+        operand: Option<Box<Expression>>,
+        branches: Vec<(Expression, Expression)>,
+        else_result: Option<Box<Expression>>,
+    },
+}
+
+/// A literal value taken out of a statement by `Statement::parameterize`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Literal {
+    Number(i64),
+    String(String),
+    Bool(bool),
+}
+
+/// A possibly schema-qualified name such as `USERS` or `MYDB.PUBLIC.USERS`, kept as its separate parts so that `"a.b".c` and `a."b.c"` stay different names. Each part is stored as the tokenizer produced it, so unquoted parts are uppercased.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QualifiedName {
+    pub parts: Vec<String>,
+}
+
+impl QualifiedName {
+    pub fn new(parts: Vec<String>) -> Self {
+        Self { parts }
+    }
+
+    /// Returns the last part, the name of the object itself without the schema it is in
+    pub fn name(&self) -> &str {
+        self.parts.last().map(String::as_str).unwrap_or_default()
+    }
+}
+
+impl From<&str> for QualifiedName {
+    fn from(name: &str) -> Self {
+        Self::new(vec![name.to_string()])
+    }
+}
+
+impl From<String> for QualifiedName {
+    fn from(name: String) -> Self {
+        Self::new(vec![name])
+    }
+}
+
+/// Where a `SELECT` reads its rows from. `Table` is a table referenced by name, which may be schema-qualified like `PUBLIC.USERS`. `only` is set by `FROM ONLY name`, which reads the table without the tables that inherit from it, and `sample` is its optional `TABLESAMPLE` clause, while `Derived` is a subquery in parentheses, `FROM (SELECT ...) AS alias`. A derived table must be given an alias, which is the name the rest of the query refers to it by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FromSource {
+    Table {
+        name: QualifiedName,
+        only: bool,
+        sample: Option<TableSample>,
+    },
+    Derived {
+        query: Box<Statement>,
+        alias: String,
+    },
+}
+
+/// A `TABLESAMPLE method (percentage)` clause, which reads only about `percentage` percent of a table's rows
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableSample {
+    pub method: SampleMethod,
+    pub percentage: u64,
+}
+
+/// How a `TABLESAMPLE` picks rows. `System` samples whole storage blocks, which is fast but clumpy, while `Bernoulli` decides for each row separately.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SampleMethod {
+    System,
+    Bernoulli,
+}
+
+/// The `GROUP BY` clause of a `SELECT`: the expressions rows are grouped by, and whether `WITH ROLLUP` asks for subtotal rows for each prefix of those expressions as well as a grand total.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GroupBy {
+    pub expressions: Vec<Expression>,
+    pub rollup: bool,
 }
 
 /// A structure containing a definition for one column, when creating a table.
 /// 1. `column_name` – A simple string, representing a name.
 /// 2. `column_type` – The type of the column. Types are defined in the `DBType` enum.
 /// 3.  `constraints` – A vector of constraints on the column. Types of constraints are defined in the `Constraint` enum.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TableColumn {
     pub column_name: String,
     pub column_type: DBType,
     pub constraints: Vec<Constraint>,
 }
 
-/// A column in the database can be any of these types. `Int` and `Bool` types have no additional info, while the `Varchar(n)` type has an additional argument – the length of the string. Adding a type, such as `DECIMAL(n, m)` is boiled down to adding tokens for that type, parsing that type and adding it to this enum.
-#[derive(Debug, PartialEq)]
+/// A column in the database can be any of these types. `SmallInt`, `Int`, `BigInt`, `Bool` and `Timestamp` types have no additional info, while the `Varchar(n)` type has an additional argument – the length of the string. Adding a type, such as `DECIMAL(n, m)` is boiled down to adding tokens for that type, parsing that type and adding it to this enum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DBType {
+    SmallInt,
     Int,
+    BigInt,
     Varchar(usize),
     Bool,
+    Timestamp,
 }
 
-/// A column can be limited to a domain of values, which is defined by constraints on that column. `PrimaryKey` and `NotNull` constraints have no additional info, while the `Check` constraints has an additional argument – the expression which every table row must satisfy.
-#[derive(Debug, PartialEq)]
+/// A column can be limited to a domain of values, which is defined by constraints on that column. `PrimaryKey`, `Unique`, `NotNull`, `Null` and `AutoIncrement` constraints have no additional info, while the `Check` constraints has an additional argument – the expression which every table row must satisfy. A `Default` constraint carries the expression used for the column when a row does not give it a value. A `Generated` constraint makes the column computed from its expression; `stored` says whether the value is saved with the row or computed when read. A `Collate` constraint names the collation that orders and compares the column's strings. A constraint declared with `CONSTRAINT name ...` is wrapped in `Named`, so the name can be used later to drop or report the constraint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Constraint {
     NotNull,
+    Null,
     PrimaryKey,
+    Unique,
+    AutoIncrement,
     ForeignKey {
         column: String,
-        referenced_table: String,
+        referenced_table: QualifiedName,
         referenced_column: String,
     },
-    Check(Expression)
+    Check(Expression),
+    Default(Expression),
+    Generated {
+        expr: Expression,
+        stored: bool,
+    },
+    Collate(String),
+    Named {
+        name: String,
+        constraint: Box<Constraint>,
+    },
+}
+
+/// The sort direction of an indexed column. A column without an explicit direction is sorted in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OrderDirection {
+    Asc,
+    Desc,
+}
+
+/// The unit of an `INTERVAL` literal. The quantity itself is kept as the string that was written in the query (`INTERVAL '7' DAY` has the value `"7"`), since it is up to the database to interpret it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IntervalUnit {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Which of the date and time values `Expression::Current` stands for. All three are taken when the statement runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CurrentValue {
+    Date,
+    Time,
+    Timestamp,
+}
+
+/// Which side a chain of the same operator groups from. `a - b - c` is `(a - b) - c` because `-` is left-associative, while `a ^ b ^ c` is `a ^ (b ^ c)` because `^` is right-associative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// Whether a quantified comparison must hold for at least one row of its subquery (`Any`) or for every row (`All`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Quantifier {
+    Any,
+    All,
 }
 
 /// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BinaryOperator {
     Plus,
     Minus,
     Multiply,
     Divide,
+    Power,
     GreaterThan,
     GreaterThanOrEqual,
     LessThan,
@@ -433,14 +765,659 @@ pub enum BinaryOperator {
     Or,
 }
 
-/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. `NullsFirst` and `NullsLast` wrap an `ORDER BY` item, along with its direction, to say whether null values sort before or after the others, and have the same lowest precedence. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum UnaryOperator {
     Not,
     Plus,
     Minus,
     Asc,
     Desc,
+    NullsFirst,
+    NullsLast,
+}
+
+impl Statement {
+    /// Returns which kind of statement this is
+    pub fn kind(&self) -> StatementKind {
+        match self {
+            Statement::Select { .. } => StatementKind::Select,
+            Statement::CreateTable { .. } => StatementKind::CreateTable,
+            Statement::CreateTableAs { .. } => StatementKind::CreateTableAs,
+            Statement::CreateIndex { .. } => StatementKind::CreateIndex,
+            Statement::CreateView { .. } => StatementKind::CreateView,
+            Statement::With { .. } => StatementKind::With,
+            Statement::Values(_) => StatementKind::Values,
+            Statement::SetVariable { .. } => StatementKind::SetVariable,
+            Statement::Insert { .. } => StatementKind::Insert,
+            Statement::ShowTables => StatementKind::ShowTables,
+            Statement::Describe { .. } => StatementKind::Describe,
+            Statement::Begin => StatementKind::Begin,
+            Statement::Commit => StatementKind::Commit,
+            Statement::Rollback => StatementKind::Rollback,
+        }
+    }
+
+    /// Prints the statement as SQL over several lines, for reading large queries. Each clause of a `SELECT` starts on a new line, and the column definitions of a `CREATE TABLE`, common table expressions and derived tables are indented by `indent` spaces per level. The compact, single-line form is the `Display` output.
+    pub fn to_sql_pretty(&self, indent: usize) -> String {
+        let nested = |statement: &Statement| {
+            let pad = " ".repeat(indent);
+            statement
+                .to_sql_pretty(indent)
+                .lines()
+                .map(|line| format!("{}{}", pad, line))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        match self {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, limit, offset } => {
+                let columns: Vec<String> = columns.iter().map(ToString::to_string).collect();
+                let mut lines = vec![format!("SELECT {}", columns.join(", "))];
+                match from {
+                    FromSource::Table { .. } => lines.push(format!("FROM {}", from)),
+                    FromSource::Derived { query, alias } => lines.push(format!("FROM (\n{}\n) AS {}", nested(query), Ident(alias))),
+                }
+                if let Some(condition) = r#where {
+                    lines.push(format!("WHERE {}", condition));
+                }
+                if let Some(groupby) = groupby {
+                    lines.push(format!("GROUP BY {}", groupby));
+                }
+                if let Some(having) = having {
+                    lines.push(format!("HAVING {}", having));
+                }
+                if !orderby.is_empty() {
+                    let orderby: Vec<String> = orderby.iter().map(ToString::to_string).collect();
+                    lines.push(format!("ORDER BY {}", orderby.join(", ")));
+                }
+                if let Some(limit) = limit {
+                    lines.push(format!("LIMIT {}", limit));
+                }
+                if let Some(offset) = offset {
+                    lines.push(format!("OFFSET {}", offset));
+                }
+                lines.join("\n")
+            }
+            Statement::CreateTable { table_name, column_list, constraints } => {
+                let pad = " ".repeat(indent);
+                let definitions: Vec<String> = column_list
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(constraints.iter().map(ToString::to_string))
+                    .map(|definition| format!("{}{}", pad, definition))
+                    .collect();
+                format!("CREATE TABLE {} (\n{}\n)", table_name, definitions.join(",\n"))
+            }
+            Statement::With { ctes, body } => {
+                let ctes: Vec<String> = ctes
+                    .iter()
+                    .map(|(name, query)| format!("{} AS (\n{}\n)", Ident(name), nested(query)))
+                    .collect();
+                format!("WITH {}\n{}", ctes.join(", "), body.to_sql_pretty(indent))
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns the names of all columns referenced by the statement's select list, `WHERE`, `GROUP BY`, `HAVING` and `ORDER BY` clauses, in order of first appearance and without duplicates. The `*` wildcard is not a column reference and is skipped.
+    pub fn referenced_columns(&self) -> Vec<String> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    /// Returns the expressions directly inside the statement: the select list, `WHERE`, `GROUP BY`, `HAVING` and `ORDER BY` of a `SELECT`, the `CHECK`, `DEFAULT` and `GENERATED` expressions of a `CREATE TABLE`, the predicate of a partial index, every value of a `VALUES` list or `INSERT`, and the value a `SET` assigns. Expressions inside nested statements, such as subqueries and common table expressions, are not included.
+    pub fn child_expressions(&self) -> Vec<&Expression> {
+        match self {
+            Statement::Select { columns, r#where, groupby, having, orderby, .. } => columns
+                .iter()
+                .chain(r#where)
+                .chain(groupby.iter().flat_map(|groupby| &groupby.expressions))
+                .chain(having)
+                .chain(orderby)
+                .collect(),
+            Statement::CreateTable { column_list, constraints, .. } => column_list
+                .iter()
+                .flat_map(|column| &column.constraints)
+                .chain(constraints)
+                .filter_map(Constraint::expression)
+                .collect(),
+            Statement::CreateIndex { predicate, .. } => predicate.iter().collect(),
+            Statement::Values(rows) | Statement::Insert { rows, .. } => rows.iter().flatten().collect(),
+            Statement::SetVariable { value, .. } => vec![value],
+            Statement::CreateTableAs { .. }
+            | Statement::CreateView { .. }
+            | Statement::With { .. }
+            | Statement::ShowTables
+            | Statement::Describe { .. }
+            | Statement::Begin
+            | Statement::Commit
+            | Statement::Rollback => Vec::new(),
+        }
+    }
+
+    /// Renames every column reference named `from` to `to`, in the statement's own expressions and in nested statements such as subqueries, derived tables and common table expressions. Names are compared as they are stored, so pass unquoted names uppercased.
+    pub fn rename_identifier(&mut self, from: &str, to: &str) {
+        self.visit_expressions_mut(&mut |expr| expr.rename_node(from, to));
+    }
+
+    /// Replaces every number, string and boolean literal with a numbered parameter, `?1`, `?2`, ..., and returns the parameterized statement together with the replaced values in the same order. Literals in nested statements are replaced too, in the order they are written. A bare number in GROUP BY or ORDER BY, like the `1` in `ORDER BY 1 DESC`, is a column position rather than a value and stays as it is. This is meant for statements that have no parameters of their own, whose numbers the new ones could clash with.
+    pub fn parameterize(&self) -> (Statement, Vec<Literal>) {
+        let mut statement = self.clone();
+        let mut values = Vec::new();
+        statement.visit_expressions_mut(&mut |expr| {
+            let literal = match expr {
+                Expression::Number(n) => Literal::Number(*n),
+                Expression::String(s) => Literal::String(s.clone()),
+                Expression::Bool(b) => Literal::Bool(*b),
+                _ => return,
+            };
+            values.push(literal);
+            *expr = Expression::Parameter(Some(values.len() as u32));
+        });
+        (statement, values)
+    }
+
+    /// Calls `visit` on every expression node of the statement and of its nested statements, in the order they are written. Column positions in GROUP BY and ORDER BY are not expressions and are skipped.
+    fn visit_expressions_mut(&mut self, visit: &mut dyn FnMut(&mut Expression)) {
+        match self {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, .. } => {
+                for expr in columns {
+                    expr.visit_mut(visit);
+                }
+                if let FromSource::Derived { query, .. } = from {
+                    query.visit_expressions_mut(visit);
+                }
+                for expr in r#where
+                    .iter_mut()
+                    .chain(groupby.iter_mut().flat_map(|groupby| &mut groupby.expressions).filter(|expr| !is_position(expr)))
+                    .chain(having)
+                    .chain(orderby.iter_mut().filter(|expr| !is_position(expr)))
+                {
+                    expr.visit_mut(visit);
+                }
+            }
+            Statement::CreateTableAs { query, .. } | Statement::CreateView { query, .. } => query.visit_expressions_mut(visit),
+            Statement::With { ctes, body } => {
+                for (_, query) in ctes {
+                    query.visit_expressions_mut(visit);
+                }
+                body.visit_expressions_mut(visit);
+            }
+            _ => {
+                for expr in self.child_expressions_mut() {
+                    expr.visit_mut(visit);
+                }
+            }
+        }
+    }
+
+    /// The mutable counterpart of `child_expressions`
+    fn child_expressions_mut(&mut self) -> Vec<&mut Expression> {
+        match self {
+            Statement::Select { columns, r#where, groupby, having, orderby, .. } => columns
+                .iter_mut()
+                .chain(r#where)
+                .chain(groupby.iter_mut().flat_map(|groupby| &mut groupby.expressions))
+                .chain(having)
+                .chain(orderby)
+                .collect(),
+            Statement::CreateTable { column_list, constraints, .. } => column_list
+                .iter_mut()
+                .flat_map(|column| &mut column.constraints)
+                .chain(constraints)
+                .filter_map(Constraint::expression_mut)
+                .collect(),
+            Statement::CreateIndex { predicate, .. } => predicate.iter_mut().collect(),
+            Statement::Values(rows) | Statement::Insert { rows, .. } => rows.iter_mut().flatten().collect(),
+            Statement::SetVariable { value, .. } => vec![value],
+            Statement::CreateTableAs { .. }
+            | Statement::CreateView { .. }
+            | Statement::With { .. }
+            | Statement::ShowTables
+            | Statement::Describe { .. }
+            | Statement::Begin
+            | Statement::Commit
+            | Statement::Rollback => Vec::new(),
+        }
+    }
+
+    /// Checks that the `WHERE` clause of a `SELECT`, and of every `SELECT` nested in the statement as a derived table, common table expression or view query, does not call an aggregate function (`COUNT`, `SUM`, `AVG`, `MIN` or `MAX`). `WHERE` filters single rows before they are grouped, so a condition on an aggregate belongs in `HAVING`. Aggregates in the select list and in `HAVING` are allowed, as are aggregates inside a subquery of the `WHERE` clause, which are evaluated on their own.
+    ///
+    /// # Returns
+    /// `Ok(())` if the statement is valid, or `Error::MisplacedAggregate` naming the first aggregate found in `WHERE`
+    pub fn validate_aggregates(&self) -> Result<(), Error> {
+        if let Statement::Select { r#where: Some(condition), .. } = self {
+            if let Some(name) = condition.find_aggregate() {
+                return Err(Error::MisplacedAggregate(format!("{} is not allowed in WHERE, use HAVING instead", name)));
+            }
+        }
+        // Derived tables, common table expressions and the queries of views have WHERE clauses of their own
+        self.nested_statements().into_iter().try_for_each(Statement::validate_aggregates)
+    }
+
+    /// Returns the statements nested directly in this one: a derived table, the query of `CREATE VIEW` or `CREATE TABLE ... AS`, or the common table expressions and body of a `WITH`. Subqueries inside expressions are not included.
+    fn nested_statements(&self) -> Vec<&Statement> {
+        match self {
+            Statement::Select { from: FromSource::Derived { query, .. }, .. }
+            | Statement::CreateTableAs { query, .. }
+            | Statement::CreateView { query, .. } => vec![query],
+            Statement::With { ctes, body } => ctes.iter().map(|(_, query)| query).chain([&**body]).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks the `CHECK` constraints of a `CREATE TABLE`, column-level and table-level alike: each must be a condition rather than a bare literal, and may only refer to columns the table declares. Any other statement is valid.
+    ///
+    /// # Returns
+    /// `Ok(())` if every check is valid, or `Error::ParserError` naming the first literal or undeclared column found
+    pub fn validate_checks(&self) -> Result<(), Error> {
+        let Statement::CreateTable { column_list, constraints, .. } = self else {
+            return Ok(());
+        };
+        let checks = column_list
+            .iter()
+            .flat_map(|column| &column.constraints)
+            .chain(constraints)
+            .filter_map(|constraint| match constraint.unnamed() {
+                Constraint::Check(expr) => Some(expr),
+                _ => None,
+            });
+        for check in checks {
+            if let Expression::Number(_) | Expression::String(_) | Expression::Bool(_) | Expression::Unknown = check {
+                return Err(Error::ParserError(format!("CHECK must be a condition, not the literal {}", check)));
+            }
+            let mut referenced = Vec::new();
+            check.collect_columns(&mut referenced);
+            if let Some(unknown) = referenced.iter().find(|name| !column_list.iter().any(|column| &column.column_name == *name)) {
+                return Err(Error::ParserError(format!("CHECK references undeclared column {}", unknown)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the nodes of the statement's syntax tree: the statement itself, every expression node, and the nodes of nested statements such as derived tables, subqueries and common table expressions. A host can use this to reject overly complex queries.
+    pub fn node_count(&self) -> usize {
+        let nested = self.nested_statements().into_iter().map(Statement::node_count).sum::<usize>();
+        1 + nested + self.child_expressions().iter().map(|expr| expr.node_count()).sum::<usize>()
+    }
+
+    /// Renders a one-line overview of the statement, like `Select(2 cols, from=USERS, where=yes, order=1)`, for logging and quick inspection where `{:#?}` would be too long
+    pub fn summary(&self) -> String {
+        let kind = self.kind();
+        match self {
+            Statement::Select { columns, from, r#where, orderby, .. } => {
+                let from = from.as_table_name().map_or_else(|| "subquery".to_string(), QualifiedName::to_string);
+                let r#where = if r#where.is_some() { "yes" } else { "no" };
+                format!("{:?}({} cols, from={}, where={}, order={})", kind, columns.len(), from, r#where, orderby.len())
+            }
+            Statement::CreateTable { table_name, column_list, .. } => format!("{:?}({}, {} cols)", kind, table_name, column_list.len()),
+            Statement::CreateTableAs { table_name, .. } => format!("{:?}({})", kind, table_name),
+            Statement::CreateIndex { index_name, table_name, .. } => format!("{:?}({} on {})", kind, index_name, table_name),
+            Statement::CreateView { view_name, .. } => format!("{:?}({})", kind, view_name),
+            Statement::With { ctes, body } => format!("{:?}({} ctes, body={})", kind, ctes.len(), body.summary()),
+            Statement::Values(rows) => format!("{:?}({} rows)", kind, rows.len()),
+            Statement::SetVariable { name, .. } => format!("{:?}(@{})", kind, name),
+            Statement::Insert { table_name, rows, .. } => format!("{:?}({}, {} rows)", kind, table_name, rows.len()),
+            Statement::Describe { table_name } => format!("{:?}({})", kind, table_name),
+            Statement::ShowTables | Statement::Begin | Statement::Commit | Statement::Rollback => format!("{:?}", kind),
+        }
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<String>) {
+        match self {
+            Statement::Select { from, .. } => {
+                if let FromSource::Derived { query, .. } = from {
+                    query.collect_columns(columns);
+                }
+                for expr in self.child_expressions() {
+                    expr.collect_columns(columns);
+                }
+            }
+            Statement::With { ctes, body } => {
+                for (_, query) in ctes {
+                    query.collect_columns(columns);
+                }
+                body.collect_columns(columns);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl BinaryOperator {
+    /// Returns how tightly the operator binds its operands; a higher value binds tighter. These are the values the parser climbs on, so `OR` is the loosest and `^` is the tightest.
+    pub const fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+            BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEqual
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual => 4,
+            BinaryOperator::Plus | BinaryOperator::Minus => 5,
+            BinaryOperator::Multiply | BinaryOperator::Divide => 6,
+            BinaryOperator::Power => 7,
+        }
+    }
+
+    /// Returns which side a chain of operators of the same precedence groups from. Every operator is left-associative except `^`.
+    pub fn associativity(&self) -> Associativity {
+        match self {
+            BinaryOperator::Power => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+
+    /// Returns whether the operator compares its operands, which is what `ANY` and `ALL` require
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Equal
+                | BinaryOperator::NotEqual
+                | BinaryOperator::GreaterThan
+                | BinaryOperator::GreaterThanOrEqual
+                | BinaryOperator::LessThan
+                | BinaryOperator::LessThanOrEqual
+        )
+    }
+}
+
+impl Constraint {
+    /// Returns the constraint itself, looking through a `CONSTRAINT name` wrapper if there is one
+    pub fn unnamed(&self) -> &Constraint {
+        match self {
+            Constraint::Named { constraint, .. } => constraint.unnamed(),
+            constraint => constraint,
+        }
+    }
+
+    /// Returns the expression a `CHECK`, `DEFAULT` or `GENERATED` constraint carries, named or not
+    fn expression(&self) -> Option<&Expression> {
+        match self.unnamed() {
+            Constraint::Check(expr) | Constraint::Default(expr) | Constraint::Generated { expr, .. } => Some(expr),
+            _ => None,
+        }
+    }
+
+    fn expression_mut(&mut self) -> Option<&mut Expression> {
+        match self {
+            Constraint::Named { constraint, .. } => constraint.expression_mut(),
+            Constraint::Check(expr) | Constraint::Default(expr) | Constraint::Generated { expr, .. } => Some(expr),
+            _ => None,
+        }
+    }
+}
+
+impl FromSource {
+    /// Returns the table name if the rows come straight from a table, or `None` for a derived table, so code that only handles base tables can reject other sources
+    pub fn as_table_name(&self) -> Option<&QualifiedName> {
+        match self {
+            FromSource::Table { name, .. } => Some(name),
+            FromSource::Derived { .. } => None,
+        }
+    }
+}
+
+impl Expression {
+    /// Returns the binding precedence of the expression's outermost node, on the same scale as [`BinaryOperator::precedence`]. A formatter needs parentheses around a child whose precedence is lower than its parent's. `ASC`, `DESC` and `AS` aliases bind loosest of all, `IS`, `IN`, `LIKE` and `BETWEEN` predicates bind like `=`, and literals, identifiers and function calls never need parentheses.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Expression::BinaryOperation { operator, .. } | Expression::Quantified { op: operator, .. } => operator.precedence(),
+            Expression::IsNull { .. }
+            | Expression::IsUnknown { .. }
+            | Expression::InList { .. }
+            | Expression::InSubquery { .. }
+            | Expression::Like { .. }
+            | Expression::DistinctFrom { .. }
+            | Expression::Between { .. } => BinaryOperator::Equal.precedence(),
+            Expression::And(_) => BinaryOperator::And.precedence(),
+            Expression::Or(_) => BinaryOperator::Or.precedence(),
+            Expression::UnaryOperation {
+                operator: UnaryOperator::Asc | UnaryOperator::Desc | UnaryOperator::NullsFirst | UnaryOperator::NullsLast,
+                ..
+            }
+            | Expression::Alias { .. } => 0,
+            // NOT takes everything up to the next AND or OR as its operand
+            Expression::UnaryOperation { operator: UnaryOperator::Not, .. } => BinaryOperator::And.precedence() + 1,
+            // A prefix minus or plus takes only the operand right after it
+            Expression::UnaryOperation { .. } => BinaryOperator::Power.precedence() + 1,
+            Expression::Number(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::Identifier(_)
+            | Expression::String(_)
+            | Expression::Interval { .. }
+            | Expression::Current(_)
+            | Expression::FunctionCall { .. }
+            | Expression::Row(_)
+            | Expression::Case { .. }
+            | Expression::Parameter(_)
+            | Expression::NamedParameter(_)
+            | Expression::Variable(_) => u8::MAX,
+        }
+    }
+
+    /// Counts the nodes of the expression tree, including the nodes of any subqueries it contains
+    pub fn node_count(&self) -> usize {
+        let children = match self {
+            Expression::BinaryOperation { left_operand, right_operand, .. } => left_operand.node_count() + right_operand.node_count(),
+            Expression::UnaryOperation { operand, .. }
+            | Expression::IsNull { operand, .. }
+            | Expression::IsUnknown { operand, .. }
+            | Expression::Alias { expr: operand, .. } => operand.node_count(),
+            Expression::FunctionCall { args, .. } | Expression::Row(args) | Expression::And(args) | Expression::Or(args) => {
+                args.iter().map(Expression::node_count).sum()
+            }
+            Expression::InList { expr, list, .. } => expr.node_count() + list.iter().map(Expression::node_count).sum::<usize>(),
+            Expression::Like { expr: left, pattern: right, .. } | Expression::DistinctFrom { left, right, .. } => {
+                left.node_count() + right.node_count()
+            }
+            Expression::Between { expr, low, high, .. } => expr.node_count() + low.node_count() + high.node_count(),
+            Expression::Case { .. } => self.case_children().map(Expression::node_count).sum(),
+            Expression::InSubquery { expr, subquery, .. } | Expression::Quantified { left: expr, subquery, .. } => {
+                expr.node_count() + subquery.node_count()
+            }
+            Expression::Number(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::Identifier(_)
+            | Expression::String(_)
+            | Expression::Interval { .. }
+            | Expression::Current(_)
+            | Expression::Parameter(_)
+            | Expression::NamedParameter(_)
+            | Expression::Variable(_) => 0,
+        };
+        1 + children
+    }
+
+    /// Renames every column reference named `from` to `to`, including those inside subqueries. Names are compared as they are stored, so pass unquoted names uppercased.
+    pub fn rename_identifier(&mut self, from: &str, to: &str) {
+        self.visit_mut(&mut |expr| expr.rename_node(from, to));
+    }
+
+    fn rename_node(&mut self, from: &str, to: &str) {
+        if let Expression::Identifier(name) = self {
+            if name == from {
+                *name = to.to_string();
+            }
+        }
+    }
+
+    /// Calls `visit` on the expression and then on each of its children, in the order they are written, including the expressions of subqueries
+    fn visit_mut(&mut self, visit: &mut dyn FnMut(&mut Expression)) {
+        visit(self);
+        match self {
+            Expression::BinaryOperation { left_operand, right_operand, .. } => {
+                left_operand.visit_mut(visit);
+                right_operand.visit_mut(visit);
+            }
+            Expression::UnaryOperation { operand, .. }
+            | Expression::IsNull { operand, .. }
+            | Expression::IsUnknown { operand, .. }
+            | Expression::Alias { expr: operand, .. } => operand.visit_mut(visit),
+            Expression::FunctionCall { args, .. } | Expression::Row(args) | Expression::And(args) | Expression::Or(args) => {
+                for arg in args {
+                    arg.visit_mut(visit);
+                }
+            }
+            Expression::InList { expr, list, .. } => {
+                expr.visit_mut(visit);
+                for item in list {
+                    item.visit_mut(visit);
+                }
+            }
+            Expression::Like { expr: left, pattern: right, .. } | Expression::DistinctFrom { left, right, .. } => {
+                left.visit_mut(visit);
+                right.visit_mut(visit);
+            }
+            Expression::Between { expr, low, high, .. } => {
+                expr.visit_mut(visit);
+                low.visit_mut(visit);
+                high.visit_mut(visit);
+            }
+            Expression::Case { operand, branches, else_result } => {
+                if let Some(operand) = operand {
+                    operand.visit_mut(visit);
+                }
+                for (condition, result) in branches {
+                    condition.visit_mut(visit);
+                    result.visit_mut(visit);
+                }
+                if let Some(else_result) = else_result {
+                    else_result.visit_mut(visit);
+                }
+            }
+            Expression::InSubquery { expr, subquery, .. } | Expression::Quantified { left: expr, subquery, .. } => {
+                expr.visit_mut(visit);
+                subquery.visit_expressions_mut(visit);
+            }
+            Expression::Number(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::Identifier(_)
+            | Expression::String(_)
+            | Expression::Interval { .. }
+            | Expression::Current(_)
+            | Expression::Parameter(_)
+            | Expression::NamedParameter(_)
+            | Expression::Variable(_) => {}
+        }
+    }
+
+    /// Returns the name of the first aggregate function called in the expression, without looking into subqueries
+    fn find_aggregate(&self) -> Option<&str> {
+        match self {
+            Expression::FunctionCall { name, args } => {
+                if AGGREGATE_FUNCTIONS.iter().any(|aggregate| name.eq_ignore_ascii_case(aggregate)) {
+                    Some(name)
+                } else {
+                    args.iter().find_map(Expression::find_aggregate)
+                }
+            }
+            Expression::BinaryOperation { left_operand, right_operand, .. } => {
+                left_operand.find_aggregate().or_else(|| right_operand.find_aggregate())
+            }
+            Expression::UnaryOperation { operand, .. }
+            | Expression::IsNull { operand, .. }
+            | Expression::IsUnknown { operand, .. }
+            | Expression::Alias { expr: operand, .. }
+            | Expression::InSubquery { expr: operand, .. }
+            | Expression::Quantified { left: operand, .. } => operand.find_aggregate(),
+            Expression::Row(items) | Expression::And(items) | Expression::Or(items) => items.iter().find_map(Expression::find_aggregate),
+            Expression::InList { expr, list, .. } => expr.find_aggregate().or_else(|| list.iter().find_map(Expression::find_aggregate)),
+            Expression::Like { expr: left, pattern: right, .. } | Expression::DistinctFrom { left, right, .. } => {
+                left.find_aggregate().or_else(|| right.find_aggregate())
+            }
+            Expression::Between { expr, low, high, .. } => expr
+                .find_aggregate()
+                .or_else(|| low.find_aggregate())
+                .or_else(|| high.find_aggregate()),
+            Expression::Case { .. } => self.case_children().find_map(Expression::find_aggregate),
+            Expression::Number(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::Identifier(_)
+            | Expression::String(_)
+            | Expression::Interval { .. }
+            | Expression::Current(_)
+            | Expression::Parameter(_)
+            | Expression::NamedParameter(_)
+            | Expression::Variable(_) => None,
+        }
+    }
+
+    /// Returns the parts of a `CASE` expression in the order they are written: the operand, each condition and its result, and the `ELSE` result. Any other expression has none.
+    fn case_children(&self) -> impl Iterator<Item = &Expression> {
+        let (operand, branches, else_result) = match self {
+            Expression::Case { operand, branches, else_result } => (operand.as_deref(), branches.as_slice(), else_result.as_deref()),
+            _ => (None, &[][..], None),
+        };
+        operand
+            .into_iter()
+            .chain(branches.iter().flat_map(|(condition, result)| [condition, result]))
+            .chain(else_result)
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<String>) {
+        match self {
+            Expression::Identifier(name) => {
+                if name != "*" && !columns.contains(name) {
+                    columns.push(name.clone());
+                }
+            }
+            Expression::BinaryOperation { left_operand, right_operand, .. } => {
+                left_operand.collect_columns(columns);
+                right_operand.collect_columns(columns);
+            }
+            Expression::UnaryOperation { operand, .. }
+            | Expression::IsNull { operand, .. }
+            | Expression::IsUnknown { operand, .. }
+            | Expression::Alias { expr: operand, .. } => {
+                operand.collect_columns(columns);
+            }
+            Expression::FunctionCall { args, .. } | Expression::Row(args) | Expression::And(args) | Expression::Or(args) => {
+                for arg in args {
+                    arg.collect_columns(columns);
+                }
+            }
+            Expression::InList { expr, list, .. } => {
+                expr.collect_columns(columns);
+                for item in list {
+                    item.collect_columns(columns);
+                }
+            }
+            Expression::Like { expr: left, pattern: right, .. } | Expression::DistinctFrom { left, right, .. } => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+            Expression::Between { expr, low, high, .. } => {
+                expr.collect_columns(columns);
+                low.collect_columns(columns);
+                high.collect_columns(columns);
+            }
+            Expression::Case { .. } => {
+                for child in self.case_children() {
+                    child.collect_columns(columns);
+                }
+            }
+            Expression::InSubquery { expr, subquery, .. } | Expression::Quantified { left: expr, subquery, .. } => {
+                expr.collect_columns(columns);
+                subquery.collect_columns(columns);
+            }
+            Expression::Number(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::String(_)
+            | Expression::Interval { .. }
+            | Expression::Current(_)
+            | Expression::Parameter(_)
+            | Expression::NamedParameter(_)
+            | Expression::Variable(_) => {}
+        }
+    }
 }
 
 // Example manual implementations for Display traits.
@@ -455,6 +1432,8 @@ impl Display for UnaryOperator {
             UnaryOperator::Plus => write!(f, "+"),
             UnaryOperator::Desc => write!(f, "DESC"),
             UnaryOperator::Asc => write!(f, "ASC"),
+            UnaryOperator::NullsFirst => write!(f, "NULLS FIRST"),
+            UnaryOperator::NullsLast => write!(f, "NULLS LAST"),
             UnaryOperator::Not => write!(f, "NOT"),
         }
     }
@@ -471,6 +1450,7 @@ impl Display for BinaryOperator {
             BinaryOperator::NotEqual => write!(f, "!="),
             BinaryOperator::Multiply => write!(f, "*"),
             BinaryOperator::Divide => write!(f, "/"),
+            BinaryOperator::Power => write!(f, "^"),
             BinaryOperator::Minus => write!(f, "-"),
             BinaryOperator::Plus => write!(f, "+"),
             BinaryOperator::And => write!(f, "AND"),
@@ -479,19 +1459,771 @@ impl Display for BinaryOperator {
     }
 }
 
+impl Display for OrderDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderDirection::Asc => write!(f, "ASC"),
+            OrderDirection::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+impl Display for Quantifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantifier::Any => write!(f, "ANY"),
+            Quantifier::All => write!(f, "ALL"),
+        }
+    }
+}
+
+impl Display for IntervalUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalUnit::Year => write!(f, "YEAR"),
+            IntervalUnit::Month => write!(f, "MONTH"),
+            IntervalUnit::Day => write!(f, "DAY"),
+            IntervalUnit::Hour => write!(f, "HOUR"),
+            IntervalUnit::Minute => write!(f, "MINUTE"),
+            IntervalUnit::Second => write!(f, "SECOND"),
+        }
+    }
+}
+
+impl Display for CurrentValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrentValue::Date => write!(f, "CURRENT_DATE"),
+            CurrentValue::Time => write!(f, "CURRENT_TIME"),
+            CurrentValue::Timestamp => write!(f, "CURRENT_TIMESTAMP"),
+        }
+    }
+}
+
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Expression::BinaryOperation { left_operand, operator, right_operand } => {
-                write!(f, "({:?} {:?} {:?})", left_operand, operator, right_operand)
+                write!(f, "({} {} {})", left_operand, operator, right_operand)
+            }
+            // Sort directions and null orderings follow their operand, as they do in an ORDER BY clause
+            Expression::UnaryOperation {
+                operand,
+                operator: operator @ (UnaryOperator::Asc | UnaryOperator::Desc | UnaryOperator::NullsFirst | UnaryOperator::NullsLast),
+            } => {
+                write!(f, "{} {}", operand, operator)
             }
             Expression::UnaryOperation { operand, operator } => {
-                write!(f, "({:?} {:?})", operator, operand)
+                write!(f, "({} {})", operator, operand)
             }
+            // A negative number is parenthesized, so its sign reads back attached to it wherever it is printed
+            Expression::Number(num) if *num < 0 => write!(f, "({num})"),
             Expression::Number(num) => write!(f, "{num}"),
-            Expression::Identifier(iden) => write!(f, "{}", iden),
-            Expression::String(str) => write!(f, "\"{}\"", str),
-            Expression::Bool(b) => write!(f, "{}", b)
+            // The `*` of a select list or `COUNT(*)` is kept as an identifier, but it is not a name
+            Expression::Identifier(iden) if iden == "*" => write!(f, "*"),
+            Expression::Identifier(iden) => write!(f, "{}", Ident(iden)),
+            Expression::String(str) => write_string_literal(f, str),
+            Expression::Bool(b) => write!(f, "{}", b),
+            Expression::Unknown => write!(f, "UNKNOWN"),
+            Expression::Interval { value, unit } => {
+                write!(f, "INTERVAL ")?;
+                write_string_literal(f, value)?;
+                write!(f, " {}", unit)
+            }
+            Expression::Current(value) => write!(f, "{}", value),
+            Expression::FunctionCall { name, args } => {
+                write!(f, "{}(", Ident(name))?;
+                write_separated(f, args, ", ")?;
+                write!(f, ")")
+            }
+            Expression::Row(items) => {
+                write!(f, "(")?;
+                write_separated(f, items, ", ")?;
+                write!(f, ")")
+            }
+            Expression::Parameter(None) => write!(f, "?"),
+            Expression::Parameter(Some(index)) => write!(f, "?{}", index),
+            Expression::NamedParameter(name) => write!(f, "{}", name),
+            Expression::Variable(name) => write!(f, "@{}", name),
+            Expression::IsNull { operand, negated: false } => write!(f, "({} IS NULL)", operand),
+            Expression::IsNull { operand, negated: true } => write!(f, "({} IS NOT NULL)", operand),
+            Expression::IsUnknown { operand, negated: false } => write!(f, "({} IS UNKNOWN)", operand),
+            Expression::IsUnknown { operand, negated: true } => write!(f, "({} IS NOT UNKNOWN)", operand),
+            Expression::And(operands) => {
+                write!(f, "(")?;
+                write_separated(f, operands, " AND ")?;
+                write!(f, ")")
+            }
+            Expression::Or(operands) => {
+                write!(f, "(")?;
+                write_separated(f, operands, " OR ")?;
+                write!(f, ")")
+            }
+            Expression::InList { expr, list, negated } => {
+                write!(f, "({} {}IN (", expr, if *negated { "NOT " } else { "" })?;
+                write_separated(f, list, ", ")?;
+                write!(f, "))")
+            }
+            Expression::InSubquery { expr, subquery, negated } => {
+                write!(f, "({} {}IN ({}))", expr, if *negated { "NOT " } else { "" }, subquery)
+            }
+            Expression::Quantified { left, op, quantifier, subquery } => {
+                write!(f, "({} {} {} ({}))", left, op, quantifier, subquery)
+            }
+            Expression::Alias { expr, alias } => write!(f, "{} AS {}", expr, Ident(alias)),
+            Expression::Like { expr, pattern, negated, escape } => {
+                write!(f, "({} {}LIKE {}", expr, if *negated { "NOT " } else { "" }, pattern)?;
+                if let Some(escape) = escape {
+                    write!(f, " ESCAPE ")?;
+                    write_string_literal(f, &escape.to_string())?;
+                }
+                write!(f, ")")
+            }
+            Expression::DistinctFrom { left, right, negated } => {
+                write!(f, "({} IS {}DISTINCT FROM {})", left, if *negated { "NOT " } else { "" }, right)
+            }
+            Expression::Between { expr, low, high, negated } => {
+                write!(f, "({} {}BETWEEN {} AND {})", expr, if *negated { "NOT " } else { "" }, low, high)
+            }
+            Expression::Case { operand, branches, else_result } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (condition, result) in branches {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+        }
+    }
+}
+impl Display for QualifiedName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", Ident(part))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for FromSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromSource::Table { name, only, sample } => {
+                if *only {
+                    write!(f, "ONLY ")?;
+                }
+                write!(f, "{}", name)?;
+                if let Some(sample) = sample {
+                    write!(f, " TABLESAMPLE {}", sample)?;
+                }
+                Ok(())
+            }
+            FromSource::Derived { query, alias } => write!(f, "({}) AS {}", query, Ident(alias)),
+        }
+    }
+}
+
+impl Display for TableSample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let method = match self.method {
+            SampleMethod::System => "SYSTEM",
+            SampleMethod::Bernoulli => "BERNOULLI",
+        };
+        write!(f, "{} ({})", method, self.percentage)
+    }
+}
+
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_separated(f, &self.expressions, ", ")?;
+        if self.rollup {
+            write!(f, " WITH ROLLUP")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DBType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DBType::SmallInt => write!(f, "SMALLINT"),
+            DBType::Int => write!(f, "INT"),
+            DBType::BigInt => write!(f, "BIGINT"),
+            DBType::Varchar(length) => write!(f, "VARCHAR({})", length),
+            DBType::Bool => write!(f, "BOOL"),
+            DBType::Timestamp => write!(f, "TIMESTAMP"),
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::NotNull => write!(f, "NOT NULL"),
+            Constraint::Null => write!(f, "NULL"),
+            Constraint::PrimaryKey => write!(f, "PRIMARY KEY"),
+            Constraint::Unique => write!(f, "UNIQUE"),
+            Constraint::AutoIncrement => write!(f, "AUTO_INCREMENT"),
+            Constraint::ForeignKey { column, referenced_table, referenced_column } => {
+                write!(f, "FOREIGN KEY ({}) REFERENCES {}({})", Ident(column), referenced_table, Ident(referenced_column))
+            }
+            Constraint::Check(expr) => write!(f, "CHECK ({})", expr),
+            Constraint::Default(expr) => write!(f, "DEFAULT {}", expr),
+            Constraint::Generated { expr, stored } => {
+                write!(f, "GENERATED ALWAYS AS ({}) {}", expr, if *stored { "STORED" } else { "VIRTUAL" })
+            }
+            Constraint::Collate(collation) => {
+                write!(f, "COLLATE ")?;
+                write_string_literal(f, collation)
+            }
+            Constraint::Named { name, constraint } => write!(f, "CONSTRAINT {} {}", Ident(name), constraint),
+        }
+    }
+}
+
+impl Display for TableColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", Ident(&self.column_name), self.column_type)?;
+        for constraint in &self.constraints {
+            write!(f, " {}", constraint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Statements are printed as SQL on a single line, without the terminating semicolon, so a `SELECT` can be embedded as a subquery
+///
+/// The output is Postgres SQL: a name that would not read back unquoted is put in double quotes, and strings are single-quoted. Names parsed under `Dialect::Generic` never need quotes, so its output reads back there as well, but a name quoted with backticks under `Dialect::MySql` prints with double quotes and only reads back as Postgres.
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Statement::Select { columns, from, r#where, groupby, having, orderby, limit, offset } => {
+                write!(f, "SELECT ")?;
+                write_separated(f, columns, ", ")?;
+                write!(f, " FROM {}", from)?;
+                if let Some(condition) = r#where {
+                    write!(f, " WHERE {}", condition)?;
+                }
+                if let Some(groupby) = groupby {
+                    write!(f, " GROUP BY {}", groupby)?;
+                }
+                if let Some(having) = having {
+                    write!(f, " HAVING {}", having)?;
+                }
+                if !orderby.is_empty() {
+                    write!(f, " ORDER BY ")?;
+                    write_separated(f, orderby, ", ")?;
+                }
+                if let Some(limit) = limit {
+                    write!(f, " LIMIT {}", limit)?;
+                }
+                if let Some(offset) = offset {
+                    write!(f, " OFFSET {}", offset)?;
+                }
+                Ok(())
+            }
+            Statement::CreateTable { table_name, column_list, constraints } => {
+                write!(f, "CREATE TABLE {} (", table_name)?;
+                write_separated(f, column_list, ", ")?;
+                for constraint in constraints {
+                    write!(f, ", {}", constraint)?;
+                }
+                write!(f, ")")
+            }
+            Statement::CreateIndex { is_unique, index_name, table_name, columns, predicate } => {
+                write!(f, "CREATE {}INDEX {} ON {} (", if *is_unique { "UNIQUE " } else { "" }, Ident(index_name), table_name)?;
+                for (i, (column, direction)) in columns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} {}", Ident(column), direction)?;
+                }
+                write!(f, ")")?;
+                if let Some(predicate) = predicate {
+                    write!(f, " WHERE {}", predicate)?;
+                }
+                Ok(())
+            }
+            Statement::CreateTableAs { table_name, query } => {
+                write!(f, "CREATE TABLE {} AS {}", table_name, query)
+            }
+            Statement::CreateView { view_name, columns, query } => {
+                write!(f, "CREATE VIEW {}", view_name)?;
+                if !columns.is_empty() {
+                    write!(f, " (")?;
+                    write_identifiers(f, columns, ", ")?;
+                    write!(f, ")")?;
+                }
+                write!(f, " AS {}", query)
+            }
+            Statement::With { ctes, body } => {
+                write!(f, "WITH ")?;
+                for (i, (name, query)) in ctes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} AS ({})", Ident(name), query)?;
+                }
+                write!(f, " {}", body)
+            }
+            Statement::Values(rows) => {
+                write!(f, "VALUES ")?;
+                write_rows(f, rows)
+            }
+            Statement::SetVariable { name, value } => write!(f, "SET @{} = {}", name, value),
+            Statement::Insert { table_name, columns, rows } => {
+                write!(f, "INSERT INTO {}", table_name)?;
+                if !columns.is_empty() {
+                    write!(f, " (")?;
+                    write_identifiers(f, columns, ", ")?;
+                    write!(f, ")")?;
+                }
+                write!(f, " VALUES ")?;
+                write_rows(f, rows)
+            }
+            Statement::ShowTables => write!(f, "SHOW TABLES"),
+            Statement::Describe { table_name } => write!(f, "DESCRIBE {}", table_name),
+            Statement::Begin => write!(f, "BEGIN"),
+            Statement::Commit => write!(f, "COMMIT"),
+            Statement::Rollback => write!(f, "ROLLBACK"),
+        }
+    }
+}
+
+/// Returns whether a GROUP BY or ORDER BY item is a column position, like the `1` in `ORDER BY 1 DESC`, rather than an expression
+fn is_position(expr: &Expression) -> bool {
+    match expr {
+        Expression::Number(_) => true,
+        Expression::UnaryOperation {
+            operand,
+            operator: UnaryOperator::Asc | UnaryOperator::Desc | UnaryOperator::NullsFirst | UnaryOperator::NullsLast,
+        } => is_position(operand),
+        _ => false,
+    }
+}
+
+/// A name printed as it has to be spelled to read back as the same identifier: bare if it is what the tokenizer makes of an unquoted word, otherwise in double quotes with any double quote inside it doubled
+struct Ident<'a>(&'a str);
+
+impl Display for Ident<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut chars = self.0.chars();
+        let bare = chars.next().is_some_and(|c| c.is_ascii_uppercase() || c == '_')
+            && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            && !lookup_keyword(self.0).is_some_and(|keyword| keyword.is_reserved());
+        if bare {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        }
+    }
+}
+
+/// Writes `value` as a single-quoted string literal, doubling any single quote inside it so the output reads back as the same string
+fn write_string_literal(f: &mut Formatter<'_>, value: &str) -> std::fmt::Result {
+    write!(f, "'{}'", value.replace('\'', "''"))
+}
+
+/// Writes a list of names with `write_separated`, each spelled so it reads back as the same identifier
+fn write_identifiers(f: &mut Formatter<'_>, names: &[String], separator: &str) -> std::fmt::Result {
+    write_separated(f, &names.iter().map(|name| Ident(name)).collect::<Vec<_>>(), separator)
+}
+
+fn write_separated<T: Display>(f: &mut Formatter<'_>, items: &[T], separator: &str) -> std::fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl Display for CommentedStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for comment in &self.leading_comments {
+            writeln!(f, "--{}", comment)?;
+        }
+        write!(f, "{}", self.statement)
+    }
+}
+
+/// Parses a single statement with `crate::parse`, so `"SELECT a FROM t;".parse::<Statement>()` works
+impl FromStr for Statement {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        crate::parse(input)
+    }
+}
+
+fn write_rows(f: &mut Formatter<'_>, rows: &[Vec<Expression>]) -> std::fmt::Result {
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "(")?;
+        write_separated(f, row, ", ")?;
+        write!(f, ")")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{binary, ident, num, unary};
+    use crate::parse;
+
+    #[test]
+    fn test_referenced_columns() {
+        let stmt = parse("SELECT a + b FROM t WHERE c > 1 ORDER BY d;").unwrap();
+        assert_eq!(stmt.referenced_columns(), vec!["A", "B", "C", "D"]);
+    }
+
+    #[test]
+    fn test_referenced_columns_skips_wildcard_and_duplicates() {
+        let stmt = parse("SELECT * FROM t WHERE COALESCE(a, b) > a AND c IS NOT NULL ORDER BY a DESC;").unwrap();
+        assert_eq!(stmt.referenced_columns(), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_multiplication_binds_tighter_than_addition() {
+        let product = binary(ident("A"), BinaryOperator::Multiply, num(2));
+        let sum = binary(ident("A"), BinaryOperator::Plus, num(2));
+        assert!(product.precedence() > sum.precedence());
+    }
+
+    #[test]
+    fn test_precedence_of_non_binary_nodes() {
+        let sum = binary(num(1), BinaryOperator::Plus, num(2));
+        assert!(num(1).precedence() > sum.precedence());
+        assert!(unary(UnaryOperator::Minus, num(1)).precedence() > sum.precedence());
+        assert_eq!(unary(UnaryOperator::Desc, ident("A")).precedence(), 0);
+    }
+
+    #[test]
+    fn test_not_binds_tighter_than_and() {
+        let stmt = parse("SELECT a FROM t WHERE NOT a AND b;").unwrap();
+        let Statement::Select { r#where: Some(condition), .. } = stmt else {
+            panic!("Expected a SELECT with a WHERE clause");
+        };
+        let Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, .. } = &condition else {
+            panic!("Expected AND at the top, got {:?}", condition);
+        };
+        assert_eq!(left_operand.precedence(), BinaryOperator::And.precedence() + 1);
+        assert!(left_operand.precedence() > condition.precedence());
+    }
+
+    #[test]
+    fn test_prefix_sign_binds_tighter_than_binary_operators() {
+        let stmt = parse("SELECT -5 + 1, -a ^ 2 FROM t;").unwrap();
+        let Statement::Select { columns, .. } = stmt else {
+            panic!("Expected a SELECT");
+        };
+        for column in &columns {
+            let Expression::BinaryOperation { left_operand, .. } = column else {
+                panic!("Expected a binary operation at the top, got {:?}", column);
+            };
+            assert!(matches!(**left_operand, Expression::UnaryOperation { operator: UnaryOperator::Minus, .. }));
+            assert!(left_operand.precedence() > column.precedence());
+        }
+    }
+
+    #[test]
+    fn test_binary_operator_display() {
+        let cases = [
+            (BinaryOperator::Plus, "+"),
+            (BinaryOperator::Minus, "-"),
+            (BinaryOperator::Multiply, "*"),
+            (BinaryOperator::Divide, "/"),
+            (BinaryOperator::Power, "^"),
+            (BinaryOperator::Equal, "="),
+            (BinaryOperator::NotEqual, "!="),
+            (BinaryOperator::GreaterThan, ">"),
+            (BinaryOperator::GreaterThanOrEqual, ">="),
+            (BinaryOperator::LessThan, "<"),
+            (BinaryOperator::LessThanOrEqual, "<="),
+            (BinaryOperator::And, "AND"),
+            (BinaryOperator::Or, "OR"),
+        ];
+        for (operator, expected) in cases {
+            assert_eq!(operator.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_unary_operator_display() {
+        assert_eq!(UnaryOperator::Minus.to_string(), "-");
+        assert_eq!(UnaryOperator::Plus.to_string(), "+");
+        assert_eq!(UnaryOperator::Not.to_string(), "NOT");
+    }
+
+    #[test]
+    fn test_expression_display_uses_operator_symbols() {
+        let expr = binary(unary(UnaryOperator::Minus, ident("A")), BinaryOperator::GreaterThanOrEqual, num(2));
+        assert_eq!(expr.to_string(), "((- A) >= 2)");
+    }
+
+    #[test]
+    fn test_statement_display_round_trips() {
+        let sql = "SELECT ID, NAME FROM USERS WHERE LEVEL > 2 ORDER BY NAME DESC";
+        let stmt = parse(&format!("{};", sql)).unwrap();
+        assert_eq!(stmt.to_string(), "SELECT ID, NAME FROM USERS WHERE (LEVEL > 2) ORDER BY NAME DESC");
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+
+        let sql = "SELECT ID, NAME FROM USERS WHERE ID NOT IN (SELECT BANNED_ID FROM BANS WHERE (LEVEL > 2)) ORDER BY NAME DESC";
+        let stmt = parse(&format!("{};", sql)).unwrap();
+        assert_eq!(stmt.to_string(), "SELECT ID, NAME FROM USERS WHERE (ID NOT IN (SELECT BANNED_ID FROM BANS WHERE (LEVEL > 2))) ORDER BY NAME DESC");
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+
+        let stmt = parse("CREATE TABLE t (id INT PRIMARY KEY, name VARCHAR(10) NOT NULL DEFAULT 'x', CHECK (id > 0));").unwrap();
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_statement_display_quotes_names_for_postgres() {
+        use crate::dialect::Dialect;
+        use crate::parse_with_dialect;
+
+        for sql in [
+            "SELECT \"Order\", a AS \"Total\" FROM t WHERE \"select\" = 'it''s';",
+            "SELECT x FROM (SELECT a AS x FROM t) AS \"Sub\";",
+            "INSERT INTO t (\"Id\", name) VALUES (1, 'a');",
+            "CREATE TABLE \"Users\" (\"Id\" INT, CONSTRAINT \"Positive\" CHECK (\"Id\" > 0));",
+        ] {
+            let stmt = parse_with_dialect(sql, Dialect::Postgres).unwrap();
+            assert_eq!(parse_with_dialect(&format!("{};", stmt), Dialect::Postgres).unwrap(), stmt, "{}", stmt);
         }
+        let stmt = parse_with_dialect("SELECT \"Order\" FROM t WHERE a = 'x';", Dialect::Postgres).unwrap();
+        assert_eq!(stmt.to_string(), "SELECT \"Order\" FROM T WHERE (A = 'x')");
+
+        // A backtick-quoted MySQL name prints with double quotes, which MySQL reads as a string
+        let stmt = parse_with_dialect("SELECT a FROM `Order`;", Dialect::MySql).unwrap();
+        assert_eq!(stmt.to_string(), "SELECT A FROM \"Order\"");
+        assert_eq!(parse_with_dialect(&format!("{};", stmt), Dialect::Postgres).unwrap(), stmt);
+        assert!(parse_with_dialect(&format!("{};", stmt), Dialect::MySql).is_err());
+
+        // Generic names are never quoted, so the output reads back under the generic dialect
+        let stmt = parse("SELECT a, \"it's\" AS b FROM t WHERE c = 'x';").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT A, 'it''s' AS B FROM T WHERE (C = 'x')");
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_child_expressions() {
+        let stmt = parse("SELECT a, b + 1 FROM t WHERE c > 2;").unwrap();
+        assert_eq!(stmt.child_expressions(), vec![
+            &ident("A"),
+            &binary(ident("B"), BinaryOperator::Plus, num(1)),
+            &binary(ident("C"), BinaryOperator::GreaterThan, num(2)),
+        ]);
+
+        let stmt = parse("CREATE TABLE t (a INT DEFAULT 1, b INT CONSTRAINT chk_b CHECK (b > 0), CHECK (a < b));").unwrap();
+        assert_eq!(stmt.child_expressions().len(), 3);
+
+        assert!(parse("SHOW TABLES;").unwrap().child_expressions().is_empty());
+    }
+
+    #[test]
+    fn test_structurally_equal_expressions_hash_alike() {
+        use std::collections::HashMap;
+
+        let mut cache = HashMap::new();
+        cache.insert(binary(ident("A"), BinaryOperator::Plus, num(1)), 1);
+        cache.insert(parse("SELECT a + 1 FROM t;").unwrap().child_expressions()[0].clone(), 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache[&binary(ident("A"), BinaryOperator::Plus, num(1))], 2);
+    }
+
+    #[test]
+    fn test_node_count() {
+        // The statement, `a + 1` and `b > 2`
+        assert_eq!(parse("SELECT a + 1 FROM t WHERE b > 2;").unwrap().node_count(), 7);
+        // The outer statement, `x`, the derived table and its `id`
+        assert_eq!(parse("SELECT x FROM (SELECT id FROM users) AS sub;").unwrap().node_count(), 4);
+        assert_eq!(parse("SHOW TABLES;").unwrap().node_count(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_statement_summary() {
+        let cases = [
+            ("SELECT id, name FROM users WHERE age > 18 ORDER BY name;", "Select(2 cols, from=USERS, where=yes, order=1)"),
+            ("SELECT * FROM (SELECT a FROM t) AS d;", "Select(1 cols, from=subquery, where=no, order=0)"),
+            ("CREATE TABLE t (a INT, b VARCHAR(5));", "CreateTable(T, 2 cols)"),
+            ("CREATE INDEX i ON t (a);", "CreateIndex(I on T)"),
+            ("INSERT INTO t VALUES (1), (2), (3);", "Insert(T, 3 rows)"),
+            ("WITH c AS (SELECT a FROM t) SELECT a FROM c;", "With(1 ctes, body=Select(1 cols, from=C, where=no, order=0))"),
+            ("BEGIN;", "Begin"),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(parse(sql).unwrap().summary(), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_statement_kind() {
+        let cases = [
+            ("SELECT a FROM t;", StatementKind::Select),
+            ("CREATE TABLE t (a INT);", StatementKind::CreateTable),
+            ("CREATE TABLE c AS SELECT a FROM t;", StatementKind::CreateTableAs),
+            ("CREATE INDEX i ON t (a);", StatementKind::CreateIndex),
+            ("CREATE VIEW v AS SELECT a FROM t;", StatementKind::CreateView),
+            ("WITH c AS (SELECT a FROM t) SELECT a FROM c;", StatementKind::With),
+            ("VALUES (1), (2);", StatementKind::Values),
+            ("SET @x = 1;", StatementKind::SetVariable),
+            ("INSERT INTO t VALUES (1);", StatementKind::Insert),
+            ("SHOW TABLES;", StatementKind::ShowTables),
+            ("DESCRIBE t;", StatementKind::Describe),
+            ("BEGIN;", StatementKind::Begin),
+            ("COMMIT;", StatementKind::Commit),
+            ("ROLLBACK;", StatementKind::Rollback),
+        ];
+        for (sql, kind) in cases {
+            assert_eq!(parse(sql).unwrap().kind(), kind, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_statement_pretty_display() {
+        let stmt = parse("SELECT id, name FROM users WHERE id > 2 ORDER BY name DESC;").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT ID, NAME FROM USERS WHERE (ID > 2) ORDER BY NAME DESC");
+        assert_eq!(stmt.to_sql_pretty(2), "SELECT ID, NAME\nFROM USERS\nWHERE (ID > 2)\nORDER BY NAME DESC");
+
+        let stmt = parse("WITH c AS (SELECT a FROM (SELECT a FROM t) AS d) SELECT a FROM c;").unwrap();
+        assert_eq!(stmt.to_string(), "WITH C AS (SELECT A FROM (SELECT A FROM T) AS D) SELECT A FROM C");
+        assert_eq!(
+            stmt.to_sql_pretty(4),
+            "WITH C AS (\n    SELECT A\n    FROM (\n        SELECT A\n        FROM T\n    ) AS D\n)\nSELECT A\nFROM C"
+        );
+
+        let stmt = parse("CREATE TABLE t (id INT PRIMARY KEY, CHECK (id > 0));").unwrap();
+        assert_eq!(stmt.to_sql_pretty(2), "CREATE TABLE T (\n  ID INT PRIMARY KEY,\n  CHECK ((ID > 0))\n)");
+        assert_eq!(parse("SHOW TABLES;").unwrap().to_sql_pretty(2), "SHOW TABLES");
+    }
+
+    #[test]
+    fn test_statement_from_str() {
+        let stmt: Statement = "SELECT a FROM t;".parse().unwrap();
+        assert_eq!(stmt, parse("SELECT a FROM t;").unwrap());
+        assert!(matches!("SELECT a;".parse::<Statement>(), Err(Error::MissingFromClause)));
+        assert!(matches!("".parse::<Statement>(), Err(Error::EmptyInput)));
+    }
+
+    #[test]
+    fn test_validate_aggregates_rejects_where() {
+        let stmt = parse("SELECT region FROM sales WHERE COUNT(id) > 1;").unwrap();
+        assert!(matches!(stmt.validate_aggregates(), Err(Error::MisplacedAggregate(message)) if message.contains("COUNT")));
+
+        let stmt = parse("SELECT region FROM sales WHERE a = 1 AND NOT (SUM(amount) > 100);").unwrap();
+        assert!(matches!(stmt.validate_aggregates(), Err(Error::MisplacedAggregate(message)) if message.contains("SUM")));
+    }
+
+    #[test]
+    fn test_validate_aggregates_count_star() {
+        let stmt = parse("SELECT region FROM sales WHERE COUNT(*) > 1;").unwrap();
+        assert!(matches!(
+            stmt.validate_aggregates(),
+            Err(Error::MisplacedAggregate(message)) if message == "COUNT is not allowed in WHERE, use HAVING instead"
+        ));
+        assert_eq!(stmt.to_string(), "SELECT REGION FROM SALES WHERE (COUNT(*) > 1)");
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+    }
+
+    #[test]
+    fn test_validate_aggregates_nested_statements() {
+        let nested = [
+            "SELECT r FROM (SELECT region AS r FROM sales WHERE SUM(amount) > 1) AS s;",
+            "WITH big AS (SELECT region FROM sales WHERE MAX(amount) > 1) SELECT region FROM big;",
+            "WITH big AS (SELECT region FROM sales) SELECT region FROM big WHERE AVG(amount) > 1;",
+            "CREATE VIEW v AS SELECT region FROM sales WHERE COUNT(*) > 1;",
+            "CREATE TABLE t AS SELECT region FROM sales WHERE MIN(amount) > 1;",
+        ];
+        for sql in nested {
+            assert!(matches!(parse(sql).unwrap().validate_aggregates(), Err(Error::MisplacedAggregate(_))), "{}", sql);
+        }
+        let stmt = parse("SELECT r FROM (SELECT region AS r, COUNT(*) FROM sales GROUP BY region HAVING COUNT(*) > 1) AS s;").unwrap();
+        assert!(stmt.validate_aggregates().is_ok());
+    }
+
+    #[test]
+    fn test_validate_aggregates_allows_having() {
+        let stmt = parse("SELECT region, SUM(amount) FROM sales WHERE amount > 0 GROUP BY region HAVING COUNT(id) > 1;").unwrap();
+        assert!(stmt.validate_aggregates().is_ok());
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT REGION, SUM(AMOUNT) FROM SALES WHERE (AMOUNT > 0) GROUP BY REGION HAVING (COUNT(ID) > 1)"
+        );
+        assert_eq!(parse(&format!("{};", stmt)).unwrap(), stmt);
+
+        let stmt = parse("SELECT id FROM sales WHERE amount IN (SELECT MAX(amount) FROM sales);").unwrap();
+        assert!(stmt.validate_aggregates().is_ok());
+    }
+
+    #[test]
+    fn test_from_source_as_table_name() {
+        let stmt = parse("SELECT id FROM public.users;").unwrap();
+        assert!(matches!(stmt, Statement::Select { ref from, .. } if from.as_table_name().map(QualifiedName::to_string) == Some("PUBLIC.USERS".to_string())));
+
+        let stmt = parse("SELECT id FROM (SELECT id FROM users) AS u;").unwrap();
+        assert!(matches!(stmt, Statement::Select { ref from, .. } if from.as_table_name().is_none()));
+    }
+
+    #[test]
+    fn test_rename_identifier() {
+        let mut stmt = parse("SELECT name, age FROM users WHERE age > 18 AND id IN (SELECT id FROM adults WHERE age < 99) ORDER BY age DESC;").unwrap();
+        stmt.rename_identifier("AGE", "YEARS");
+        assert_eq!(
+            stmt,
+            parse("SELECT name, years FROM users WHERE years > 18 AND id IN (SELECT id FROM adults WHERE years < 99) ORDER BY years DESC;").unwrap()
+        );
+
+        let mut expr = binary(ident("AGE"), BinaryOperator::Plus, ident("AGED"));
+        expr.rename_identifier("AGE", "YEARS");
+        assert_eq!(expr, binary(ident("YEARS"), BinaryOperator::Plus, ident("AGED")));
+    }
+
+    #[test]
+    fn test_parameterize() {
+        let stmt = parse("SELECT name FROM users WHERE id = 5 AND name = 'x' ORDER BY name LIMIT 10;").unwrap();
+        let (parameterized, values) = stmt.parameterize();
+        assert_eq!(values, vec![Literal::Number(5), Literal::String("x".to_string())]);
+        assert_eq!(parameterized, parse("SELECT name FROM users WHERE id = ?1 AND name = ?2 ORDER BY name LIMIT 10;").unwrap());
+
+        let stmt = parse("SELECT 1 FROM (SELECT a FROM t WHERE b = TRUE) AS d WHERE a IN (SELECT c FROM u WHERE c > 2);").unwrap();
+        let (parameterized, values) = stmt.parameterize();
+        assert_eq!(values, vec![Literal::Number(1), Literal::Bool(true), Literal::Number(2)]);
+        assert_eq!(
+            parameterized.to_string(),
+            "SELECT ?1 FROM (SELECT A FROM T WHERE (B = ?2)) AS D WHERE (A IN (SELECT C FROM U WHERE (C > ?3)))"
+        );
+
+        // Column positions are not values, but a number inside a larger sort key is
+        let stmt = parse("SELECT a, b FROM t WHERE a > 3 GROUP BY 1, b HAVING b > 4 ORDER BY 2 DESC, a + 5;").unwrap();
+        let (parameterized, values) = stmt.parameterize();
+        assert_eq!(values, vec![Literal::Number(3), Literal::Number(4), Literal::Number(5)]);
+        assert_eq!(parameterized, parse("SELECT a, b FROM t WHERE a > ?1 GROUP BY 1, b HAVING b > ?2 ORDER BY 2 DESC, a + ?3;").unwrap());
+    }
+
+    #[test]
+    fn test_validate_checks() {
+        let stmt = parse("CREATE TABLE t (low INT CHECK (low >= 0), high INT, CONSTRAINT ordered CHECK (low < high));").unwrap();
+        assert!(stmt.validate_checks().is_ok());
+
+        let stmt = parse("CREATE TABLE t (low INT, CHECK (low < hihg));").unwrap();
+        assert!(matches!(stmt.validate_checks(), Err(Error::ParserError(msg)) if msg == "CHECK references undeclared column HIHG"));
+
+        let stmt = parse("CREATE TABLE t (low INT CHECK (TRUE));").unwrap();
+        assert!(matches!(stmt.validate_checks(), Err(Error::ParserError(msg)) if msg == "CHECK must be a condition, not the literal true"));
+
+        assert!(parse("SELECT a FROM t;").unwrap().validate_checks().is_ok());
+    }
+}
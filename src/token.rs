@@ -9,12 +9,16 @@ pub enum Token {
     Identifier(String),
     String(String),
     Number(u64),
+    Placeholder(Option<u32>),
+    NamedParameter(String),
+    Variable(String),
     
     // Operators and punctuation
     Plus,
     Minus,
     Star,
     Divide,
+    Caret,
     Equal,
     NotEqual,
     LessThan,
@@ -24,23 +28,53 @@ pub enum Token {
     LeftParentheses,
     RightParentheses,
     Comma,
+    Dot,
     Semicolon,
     Wildcard,
     
     // Special tokens
+    Comment(String),
     Eof,
 }
 
+/// Position of a token in the input. Lines and columns are counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum Keyword {
     Select,
+    With,
+    Values,
+    Insert,
+    Into,
+    As,
     Create,
     Table,
     Where,
     Order,
     By,
+    Group,
+    Having,
+    Rollup,
+    Limit,
+    Offset,
+    Fetch,
+    Next,
+    Row,
+    Rows,
+    Only,
+    Tablesample,
+    System,
+    Bernoulli,
     Asc,
     Desc,
+    Nulls,
+    First,
+    Last,
     From,
     And,
     Or,
@@ -52,13 +86,227 @@ pub enum Keyword {
     Foreign,
     References,
     Check,
+    Default,
+    Generated,
+    Always,
+    Stored,
+    Virtual,
+    Collate,
+    Constraint,
     Int,
+    SmallInt,
+    BigInt,
     Bool,
+    Timestamp,
     Varchar,
     Null,
+    Unknown,
+    Is,
+    Distinct,
+    In,
+    Like,
+    Escape,
+    Between,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    Any,
+    All,
     Index,
+    View,
     Unique,
     On,
+    Show,
+    Set,
+    Tables,
+    Describe,
+    Begin,
+    Commit,
+    Rollback,
+    Transaction,
+    AutoIncrement,
+    Serial,
+    Interval,
+    CurrentDate,
+    CurrentTime,
+    CurrentTimestamp,
+    Day,
+    Month,
+    Year,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Every keyword the tokenizer recognises, with the keyword it becomes. Some keywords have more than one spelling, such as `BOOL` and `BOOLEAN`.
+const KEYWORDS: &[(&str, Keyword)] = &[
+    ("SELECT", Keyword::Select),
+    ("WITH", Keyword::With),
+    ("VALUES", Keyword::Values),
+    ("INSERT", Keyword::Insert),
+    ("INTO", Keyword::Into),
+    ("AS", Keyword::As),
+    ("CREATE", Keyword::Create),
+    ("TABLE", Keyword::Table),
+    ("WHERE", Keyword::Where),
+    ("ORDER", Keyword::Order),
+    ("BY", Keyword::By),
+    ("GROUP", Keyword::Group),
+    ("HAVING", Keyword::Having),
+    ("ROLLUP", Keyword::Rollup),
+    ("LIMIT", Keyword::Limit),
+    ("OFFSET", Keyword::Offset),
+    ("FETCH", Keyword::Fetch),
+    ("NEXT", Keyword::Next),
+    ("ROW", Keyword::Row),
+    ("ROWS", Keyword::Rows),
+    ("ONLY", Keyword::Only),
+    ("TABLESAMPLE", Keyword::Tablesample),
+    ("SYSTEM", Keyword::System),
+    ("BERNOULLI", Keyword::Bernoulli),
+    ("ASC", Keyword::Asc),
+    ("DESC", Keyword::Desc),
+    ("NULLS", Keyword::Nulls),
+    ("FIRST", Keyword::First),
+    ("LAST", Keyword::Last),
+    ("FROM", Keyword::From),
+    ("AND", Keyword::And),
+    ("OR", Keyword::Or),
+    ("NOT", Keyword::Not),
+    ("TRUE", Keyword::True),
+    ("FALSE", Keyword::False),
+    ("PRIMARY", Keyword::Primary),
+    ("KEY", Keyword::Key),
+    ("FOREIGN", Keyword::Foreign),
+    ("REFERENCES", Keyword::References),
+    ("CHECK", Keyword::Check),
+    ("DEFAULT", Keyword::Default),
+    ("GENERATED", Keyword::Generated),
+    ("ALWAYS", Keyword::Always),
+    ("STORED", Keyword::Stored),
+    ("VIRTUAL", Keyword::Virtual),
+    ("COLLATE", Keyword::Collate),
+    ("CONSTRAINT", Keyword::Constraint),
+    ("INT", Keyword::Int),
+    ("SMALLINT", Keyword::SmallInt),
+    ("BIGINT", Keyword::BigInt),
+    ("BOOL", Keyword::Bool),
+    ("TIMESTAMP", Keyword::Timestamp),
+    ("BOOLEAN", Keyword::Bool),
+    ("VARCHAR", Keyword::Varchar),
+    ("NULL", Keyword::Null),
+    ("UNKNOWN", Keyword::Unknown),
+    ("IS", Keyword::Is),
+    ("DISTINCT", Keyword::Distinct),
+    ("IN", Keyword::In),
+    ("LIKE", Keyword::Like),
+    ("ESCAPE", Keyword::Escape),
+    ("BETWEEN", Keyword::Between),
+    ("CASE", Keyword::Case),
+    ("WHEN", Keyword::When),
+    ("THEN", Keyword::Then),
+    ("ELSE", Keyword::Else),
+    ("END", Keyword::End),
+    ("ANY", Keyword::Any),
+    ("ALL", Keyword::All),
+    ("INDEX", Keyword::Index),
+    ("VIEW", Keyword::View),
+    ("UNIQUE", Keyword::Unique),
+    ("ON", Keyword::On),
+    ("SHOW", Keyword::Show),
+    ("SET", Keyword::Set),
+    ("TABLES", Keyword::Tables),
+    ("DESCRIBE", Keyword::Describe),
+    ("BEGIN", Keyword::Begin),
+    ("COMMIT", Keyword::Commit),
+    ("ROLLBACK", Keyword::Rollback),
+    ("TRANSACTION", Keyword::Transaction),
+    ("AUTO_INCREMENT", Keyword::AutoIncrement),
+    ("SERIAL", Keyword::Serial),
+    ("INTERVAL", Keyword::Interval),
+    ("CURRENT_DATE", Keyword::CurrentDate),
+    ("CURRENT_TIME", Keyword::CurrentTime),
+    ("CURRENT_TIMESTAMP", Keyword::CurrentTimestamp),
+    ("DAY", Keyword::Day),
+    ("MONTH", Keyword::Month),
+    ("YEAR", Keyword::Year),
+    ("HOUR", Keyword::Hour),
+    ("MINUTE", Keyword::Minute),
+    ("SECOND", Keyword::Second),
+];
+
+/// The spellings from `KEYWORDS`, in the same order
+const KEYWORD_NAMES: [&str; KEYWORDS.len()] = {
+    let mut names = [""; KEYWORDS.len()];
+    let mut i = 0;
+    while i < KEYWORDS.len() {
+        names[i] = KEYWORDS[i].0;
+        i += 1;
+    }
+    names
+};
+
+/// Returns the keyword spelled by `word`, which must already be uppercase, or `None` if it is an ordinary identifier
+pub fn lookup_keyword(word: &str) -> Option<Keyword> {
+    KEYWORDS.iter().find(|(name, _)| *name == word).map(|(_, keyword)| keyword.clone())
+}
+
+/// Keywords that may still be used as column names, since nothing else can appear where they would be read as a name
+const NON_RESERVED_KEYWORDS: &[Keyword] = &[
+    Keyword::Key,
+    Keyword::On,
+    Keyword::Index,
+    Keyword::Asc,
+    Keyword::Desc,
+    Keyword::Nulls,
+    Keyword::First,
+    Keyword::Last,
+    Keyword::System,
+    Keyword::Bernoulli,
+    Keyword::Year,
+    Keyword::Month,
+    Keyword::Day,
+    Keyword::Hour,
+    Keyword::Minute,
+    Keyword::Second,
+    Keyword::Limit,
+    Keyword::Offset,
+    Keyword::Fetch,
+    Keyword::Next,
+    Keyword::Row,
+    Keyword::Rows,
+    Keyword::Only,
+    Keyword::Timestamp,
+    Keyword::CurrentDate,
+    Keyword::CurrentTime,
+    Keyword::CurrentTimestamp,
+    Keyword::Show,
+    Keyword::Tables,
+    Keyword::Escape,
+];
+
+impl Keyword {
+    /// Returns whether the keyword is reserved, meaning it can never be used as an identifier
+    pub fn is_reserved(&self) -> bool {
+        !NON_RESERVED_KEYWORDS.contains(self)
+    }
+
+    /// Returns the keyword as it is written in SQL, such as `AUTO_INCREMENT`. A keyword with several spellings returns the first one in `KEYWORDS`.
+    pub fn as_str(&self) -> &'static str {
+        KEYWORDS.iter().find(|(_, keyword)| keyword == self).map(|(name, _)| *name).unwrap_or_default()
+    }
+}
+
+/// Returns whether `word` is a reserved word, regardless of case
+pub fn is_keyword(word: &str) -> bool {
+    lookup_keyword(&word.to_ascii_uppercase()).is_some()
+}
+
+/// Returns the uppercase spelling of every keyword, for tooling such as autocompletion and syntax highlighting
+pub fn all_keywords() -> &'static [&'static str] {
+    &KEYWORD_NAMES
 }
 
 impl Display for Token {
@@ -68,8 +316,12 @@ impl Display for Token {
             Token::Identifier(iden) => write!(f, "{:?}", iden),
             Token::String(str) => write!(f, "{:?}", str),
             Token::Number(num) => write!(f, "{:?}", num),
-            Token::RightParentheses => write!(f, "("),
-            Token::LeftParentheses => write!(f, ")"),
+            Token::Placeholder(None) => write!(f, "?"),
+            Token::Placeholder(Some(index)) => write!(f, "?{}", index),
+            Token::NamedParameter(name) => write!(f, "{}", name),
+            Token::Variable(name) => write!(f, "@{}", name),
+            Token::RightParentheses => write!(f, ")"),
+            Token::LeftParentheses => write!(f, "("),
             Token::GreaterThan => write!(f, ">"),
             Token::GreaterThanOrEqual => write!(f, ">="),
             Token::LessThan => write!(f, "<"),
@@ -79,26 +331,56 @@ impl Display for Token {
             Token::Star => write!(f, "*"),
             Token::Wildcard => write!(f, "*"),
             Token::Divide => write!(f, "/"),
+            Token::Caret => write!(f, "^"),
             Token::Minus => write!(f, "-"),
             Token::Plus => write!(f, "+"),
             Token::Comma => write!(f, ","),
+            Token::Dot => write!(f, "."),
             Token::Semicolon => write!(f, ";"),
+            Token::Comment(text) => write!(f, "--{}", text),
             Token::Eof => write!(f, "Eof"),
         }
     }
 }
 
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 impl Display for Keyword {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Keyword::Select => write!(f, "Select"),
+            Keyword::With => write!(f, "With"),
+            Keyword::Values => write!(f, "Values"),
+            Keyword::Insert => write!(f, "Insert"),
+            Keyword::Into => write!(f, "Into"),
+            Keyword::As => write!(f, "As"),
             Keyword::Create => write!(f, "Create"),
             Keyword::Table => write!(f, "Table"),
             Keyword::Where => write!(f, "Where"),
             Keyword::Order => write!(f, "Order"),
             Keyword::By => write!(f, "By"),
+            Keyword::Group => write!(f, "Group"),
+            Keyword::Having => write!(f, "Having"),
+            Keyword::Rollup => write!(f, "Rollup"),
+            Keyword::Limit => write!(f, "Limit"),
+            Keyword::Offset => write!(f, "Offset"),
+            Keyword::Fetch => write!(f, "Fetch"),
+            Keyword::Next => write!(f, "Next"),
+            Keyword::Row => write!(f, "Row"),
+            Keyword::Rows => write!(f, "Rows"),
+            Keyword::Only => write!(f, "Only"),
+            Keyword::Tablesample => write!(f, "Tablesample"),
+            Keyword::System => write!(f, "System"),
+            Keyword::Bernoulli => write!(f, "Bernoulli"),
             Keyword::Asc => write!(f, "Asc"),
             Keyword::Desc => write!(f, "Desc"),
+            Keyword::Nulls => write!(f, "Nulls"),
+            Keyword::First => write!(f, "First"),
+            Keyword::Last => write!(f, "Last"),
             Keyword::From => write!(f, "From"),
             Keyword::And => write!(f, "And"),
             Keyword::Or => write!(f, "Or"),
@@ -110,13 +392,58 @@ impl Display for Keyword {
             Keyword::Foreign => write!(f, "Foreign"),
             Keyword::References => write!(f, "References"),
             Keyword::Check => write!(f, "Check"),
+            Keyword::Default => write!(f, "Default"),
+            Keyword::Generated => write!(f, "Generated"),
+            Keyword::Always => write!(f, "Always"),
+            Keyword::Stored => write!(f, "Stored"),
+            Keyword::Virtual => write!(f, "Virtual"),
+            Keyword::Collate => write!(f, "Collate"),
+            Keyword::Constraint => write!(f, "Constraint"),
             Keyword::Int => write!(f, "Int"),
+            Keyword::SmallInt => write!(f, "SmallInt"),
+            Keyword::BigInt => write!(f, "BigInt"),
             Keyword::Bool => write!(f, "Bool"),
+            Keyword::Timestamp => write!(f, "Timestamp"),
             Keyword::Varchar => write!(f, "Varchar"),
             Keyword::Null => write!(f, "Null"),
+            Keyword::Unknown => write!(f, "Unknown"),
+            Keyword::Is => write!(f, "Is"),
+            Keyword::Distinct => write!(f, "Distinct"),
+            Keyword::In => write!(f, "In"),
+            Keyword::Like => write!(f, "Like"),
+            Keyword::Escape => write!(f, "Escape"),
+            Keyword::Between => write!(f, "Between"),
+            Keyword::Case => write!(f, "Case"),
+            Keyword::When => write!(f, "When"),
+            Keyword::Then => write!(f, "Then"),
+            Keyword::Else => write!(f, "Else"),
+            Keyword::End => write!(f, "End"),
+            Keyword::Any => write!(f, "Any"),
+            Keyword::All => write!(f, "All"),
             Keyword::Index => write!(f, "Index"),
+            Keyword::View => write!(f, "View"),
             Keyword::Unique => write!(f, "Unique"),
             Keyword::On => write!(f, "On"),
+            Keyword::Show => write!(f, "Show"),
+            Keyword::Set => write!(f, "Set"),
+            Keyword::Tables => write!(f, "Tables"),
+            Keyword::Describe => write!(f, "Describe"),
+            Keyword::Begin => write!(f, "Begin"),
+            Keyword::Commit => write!(f, "Commit"),
+            Keyword::Rollback => write!(f, "Rollback"),
+            Keyword::Transaction => write!(f, "Transaction"),
+            Keyword::AutoIncrement => write!(f, "AutoIncrement"),
+            Keyword::Serial => write!(f, "Serial"),
+            Keyword::Interval => write!(f, "Interval"),
+            Keyword::CurrentDate => write!(f, "CurrentDate"),
+            Keyword::CurrentTime => write!(f, "CurrentTime"),
+            Keyword::CurrentTimestamp => write!(f, "CurrentTimestamp"),
+            Keyword::Day => write!(f, "Day"),
+            Keyword::Month => write!(f, "Month"),
+            Keyword::Year => write!(f, "Year"),
+            Keyword::Hour => write!(f, "Hour"),
+            Keyword::Minute => write!(f, "Minute"),
+            Keyword::Second => write!(f, "Second"),
         }
     }
 }
\ No newline at end of file
@@ -1,52 +1,358 @@
-use crate::token::{Token, Keyword};
+use crate::token::{lookup_keyword, Token, Keyword, Span};
 use crate::error::Error;
+use crate::dialect::Dialect;
 use std::iter::Peekable;
 use std::str::Chars;
 
+/// Default limit for identifier length, see `Tokenizer::with_limits`
+const DEFAULT_MAX_IDENTIFIER_LENGTH: usize = 1 << 20;
+
+/// Default limit for the digit count of a number, see `Tokenizer::with_limits`
+const DEFAULT_MAX_NUMBER_DIGITS: usize = 1 << 20;
+
 pub struct Tokenizer<'a> {
     input: Peekable<Chars<'a>>,
     is_after_select: bool,  // Track if we're after SELECT keyword
     current_position: usize,  // Track current position in input
+    line: usize,            // Line of the current position, counted from 1
+    column: usize,          // Column of the current position, counted from 1
     tokens: Vec<Token>,     // Store all tokens
+    spans: Vec<Span>,       // Start position of each token produced by `tokenize`
     current_token: usize,   // Current token index
+    max_identifier_length: usize,  // Longest identifier accepted
+    max_number_digits: usize,      // Most digits accepted in a number
+    backslash_escapes: bool,       // Whether `\` starts an escape sequence inside strings
+    dialect: Dialect,              // Dialect deciding how quotes are read
+    keep_comments: bool,           // Whether comments become tokens instead of being skipped
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::new_with_dialect(input, Dialect::Generic)
+    }
+
+    /// Creates a tokenizer for a specific SQL dialect. The dialect decides which quote character delimits identifiers and whether backslash escapes are on by default.
+    pub fn new_with_dialect(input: &'a str, dialect: Dialect) -> Self {
         Self {
             input: input.chars().peekable(),
             is_after_select: false,
             current_position: 0,
+            line: 1,
+            column: 1,
             tokens: Vec::new(),
+            spans: Vec::new(),
             current_token: 0,
+            max_identifier_length: DEFAULT_MAX_IDENTIFIER_LENGTH,
+            max_number_digits: DEFAULT_MAX_NUMBER_DIGITS,
+            backslash_escapes: dialect.backslash_escapes(),
+            dialect,
+            keep_comments: false,
+        }
+    }
+
+    /// Overrides the longest identifier and the most digits in a number that will be accepted, for use on untrusted input
+    pub fn with_limits(mut self, max_identifier_length: usize, max_number_digits: usize) -> Self {
+        self.max_identifier_length = max_identifier_length;
+        self.max_number_digits = max_number_digits;
+        self
+    }
+
+    /// Enables C-style escapes inside string literals: `\n`, `\t`, `\\` and `\'`. Off by default, where a backslash is an ordinary character as in standard SQL
+    pub fn with_backslash_escapes(mut self, enabled: bool) -> Self {
+        self.backslash_escapes = enabled;
+        self
+    }
+
+    /// Emits each `--` comment as a `Token::Comment` holding the text after the dashes, instead of discarding it. The parser skips comment tokens, so this only matters to callers reading the tokens themselves, like a formatter.
+    pub fn with_keep_comments(mut self, keep: bool) -> Self {
+        self.keep_comments = keep;
+        self
+    }
+
+    fn check_identifier_length(&self, length: usize) -> Result<(), Error> {
+        if length > self.max_identifier_length {
+            return Err(Error::LexerError(format!("Identifier longer than {} characters", self.max_identifier_length)));
         }
+        Ok(())
+    }
+
+    fn check_number_digits(&self, digits: usize) -> Result<(), Error> {
+        if digits > self.max_number_digits {
+            return Err(Error::LexerError(format!("Number longer than {} digits", self.max_number_digits)));
+        }
+        Ok(())
+    }
+
+    /// Returns the start position of every token returned by the last call to `tokenize`
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
     }
 
     fn advance(&mut self) {
-        self.input.next();
+        if let Some('\n') = self.input.next() {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
         self.current_position += 1;
     }
 
-    fn tokenize_string(&mut self, quote: char) -> Result<Token, Error> {
+    /// Reads a `--` comment up to and including the end of its line, returning the text after the dashes. The end of input also ends a comment, so a comment may be the last thing in the input.
+    fn read_line_comment(&mut self) -> String {
+        // Consume the second dash
+        self.advance();
+        let mut text = String::new();
+        while let Some(&c) = self.input.peek() {
+            self.advance();
+            if c == '\n' {
+                break;
+            }
+            text.push(c);
+        }
+        text.trim_end_matches('\r').to_string()
+    }
+
+    fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// Reads a string literal between `quote` characters. `backslash_escapes` says whether `\` starts an escape sequence, which is the tokenizer's setting except for `E'...'` strings.
+    fn tokenize_string(&mut self, quote: char, backslash_escapes: bool) -> Result<Token, Error> {
         self.advance(); // consume opening quote
         let mut string = String::new();
         
         while let Some(&c) = self.input.peek() {
             if c == quote {
                 self.advance(); // consume closing quote
+                // A doubled quote stands for one quote character inside the string
+                if self.input.peek() == Some(&quote) {
+                    self.advance();
+                    string.push(quote);
+                    continue;
+                }
                 return Ok(Token::String(string));
             }
-            string.push(c);
             self.advance();
+            if c == '\\' && backslash_escapes {
+                let escaped = match self.input.peek() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('\\') => '\\',
+                    Some('\'') => '\'',
+                    Some(other) => return Err(Error::LexerError(format!("Unknown escape sequence \\{} in string literal", other))),
+                    None => return Err(Error::LexerError("Unterminated escape sequence at end of input".to_string())),
+                };
+                self.advance();
+                string.push(escaped);
+                continue;
+            }
+            string.push(c);
         }
         
         Err(Error::LexerError(format!("Unterminated string literal starting with {}", quote)))
     }
 
+    /// Returns the tag of the dollar quote starting at the current `$`, or `None` if the `$` does not start one. `$$` has an empty tag and `$tag$` has the tag `tag`; a tag follows the identifier rules, so `$1` stays a parameter.
+    fn dollar_quote_tag(&self) -> Option<String> {
+        let mut tag = String::new();
+        // Skip the opening $, which is still the next character
+        for c in self.input.clone().skip(1) {
+            match c {
+                '$' => return Some(tag),
+                c if c.is_ascii_alphabetic() || c == '_' || (c.is_ascii_digit() && !tag.is_empty()) => tag.push(c),
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Reads a Postgres dollar-quoted string, `$tag$...$tag$`, whose opening delimiter carries `tag`. Nothing inside is escaped, so the string ends at the first closing delimiter with the same tag.
+    fn tokenize_dollar_quoted_string(&mut self, tag: &str) -> Result<Token, Error> {
+        let delimiter = format!("${}$", tag);
+        for _ in 0..delimiter.len() {
+            self.advance(); // consume the opening delimiter
+        }
+        let mut string = String::new();
+
+        while let Some(&c) = self.input.peek() {
+            self.advance();
+            string.push(c);
+            if string.ends_with(&delimiter) {
+                string.truncate(string.len() - delimiter.len());
+                return Ok(Token::String(string));
+            }
+        }
+
+        Err(Error::LexerError(format!("Unterminated dollar-quoted string starting with {}", delimiter)))
+    }
+
+    /// Reads an identifier between `quote` characters, keeping its case. A doubled quote stands for one quote character inside the name.
+    fn tokenize_quoted_identifier(&mut self, quote: char) -> Result<Token, Error> {
+        self.advance(); // consume opening quote
+        let mut identifier = String::new();
+        let mut length = 0;
+
+        while let Some(&c) = self.input.peek() {
+            self.advance();
+            if c == quote {
+                if self.input.peek() != Some(&quote) {
+                    if identifier.is_empty() {
+                        return Err(Error::LexerError("Quoted identifier cannot be empty".to_string()));
+                    }
+                    return Ok(Token::Identifier(identifier));
+                }
+                self.advance();
+            }
+            length += 1;
+            self.check_identifier_length(length)?;
+            identifier.push(c);
+        }
+
+        Err(Error::LexerError(format!("Unterminated quoted identifier starting with {}", quote)))
+    }
+
+    fn tokenize_number(&mut self) -> Result<Token, Error> {
+        let mut number = 0u64;
+        let mut digits = 0;
+
+        // A leading `0x` or `0X` switches to hexadecimal digits
+        if let Some(&'0') = self.input.peek() {
+            self.advance();
+            digits += 1;
+            if let Some(&('x' | 'X')) = self.input.peek() {
+                self.advance();
+                return self.tokenize_hex_number();
+            }
+        }
+
+        while let Some(&c) = self.input.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits += 1;
+            self.check_number_digits(digits)?;
+            if let Some(new_number) = number.checked_mul(10).and_then(|n| n.checked_add(c.to_digit(10).unwrap() as u64)) {
+                number = new_number;
+            } else {
+                return Err(Error::LexerError("Number too large".to_string()));
+            }
+            self.advance();
+        }
+        Ok(Token::Number(number))
+    }
+
+    fn tokenize_hex_number(&mut self) -> Result<Token, Error> {
+        let mut number = 0u64;
+        let mut digits = 0;
+
+        while let Some(&c) = self.input.peek() {
+            let digit = match c.to_digit(16) {
+                Some(digit) => digit as u64,
+                None => break,
+            };
+            digits += 1;
+            self.check_number_digits(digits)?;
+            if let Some(new_number) = number.checked_mul(16).and_then(|n| n.checked_add(digit)) {
+                number = new_number;
+            } else {
+                return Err(Error::LexerError("Number too large".to_string()));
+            }
+            self.advance();
+        }
+
+        if digits == 0 {
+            return Err(Error::LexerError("Expected hexadecimal digits after '0x'".to_string()));
+        }
+        Ok(Token::Number(number))
+    }
+
+    fn tokenize_placeholder(&mut self) -> Result<Token, Error> {
+        let mut index: Option<u32> = None;
+        let mut digits = 0;
+        while let Some(&c) = self.input.peek() {
+            let digit = match c.to_digit(10) {
+                Some(digit) => digit,
+                None => break,
+            };
+            digits += 1;
+            self.check_number_digits(digits)?;
+            match index.unwrap_or(0).checked_mul(10).and_then(|n| n.checked_add(digit)) {
+                Some(new_index) => index = Some(new_index),
+                None => return Err(Error::LexerError("Placeholder index too large".to_string())),
+            }
+            self.advance();
+        }
+        Ok(Token::Placeholder(index))
+    }
+
+    fn tokenize_named_parameter(&mut self, prefix: char) -> Result<Token, Error> {
+        self.advance(); // consume prefix
+        // The prefix is kept as part of the name, since drivers bind `:id` and `$id` separately
+        let mut name = String::from(prefix);
+
+        while let Some(&c) = self.input.peek() {
+            if !c.is_ascii_alphanumeric() && c != '_' {
+                break;
+            }
+            self.check_identifier_length(name.len())?;
+            name.push(c);
+            self.advance();
+        }
+
+        if name.len() == 1 {
+            return Err(Error::LexerError(format!("Expected parameter name after '{}'", prefix)));
+        }
+        Ok(Token::NamedParameter(name))
+    }
+
+    /// Reads a user variable, `@name`. The name is kept without the `@`, in the case it was written.
+    fn tokenize_variable(&mut self) -> Result<Token, Error> {
+        self.advance(); // consume @
+        let mut name = String::new();
+
+        while let Some(&c) = self.input.peek() {
+            if !c.is_ascii_alphanumeric() && c != '_' {
+                break;
+            }
+            self.check_identifier_length(name.len() + 1)?;
+            name.push(c);
+            self.advance();
+        }
+
+        if name.is_empty() {
+            return Err(Error::LexerError("Expected variable name after '@'".to_string()));
+        }
+        Ok(Token::Variable(name))
+    }
+
     pub fn tokenize(&mut self) -> Result<Vec<Token>, Error> {
         let mut tokens = Vec::new();
+        self.tokenize_into(&mut tokens, None)?;
+        Ok(tokens)
+    }
+
+    /// Tokenizes like `tokenize`, but records an invalid character as an error and skips it instead of giving up, so a linter can report every stray character in a script at once. Any other error still stops tokenization; it is returned together with the tokens read before it.
+    pub fn tokenize_lossy(&mut self) -> (Vec<Token>, Vec<Error>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        if let Err(error) = self.tokenize_into(&mut tokens, Some(&mut errors)) {
+            errors.push(error);
+            tokens.push(Token::Eof);
+            self.spans.push(self.span());
+        }
+        (tokens, errors)
+    }
+
+    /// Appends the tokens of the input to `tokens`. Invalid characters are pushed to `skipped` and passed over when it is given, and end tokenization otherwise.
+    fn tokenize_into(&mut self, tokens: &mut Vec<Token>, mut skipped: Option<&mut Vec<Error>>) -> Result<(), Error> {
+        self.spans.clear();
         
         while let Some(&c) = self.input.peek() {
+            let span = self.span();
             let token = match c {
                 ' ' | '\t' | '\n' | '\r' => {
                     self.advance();
@@ -71,15 +377,24 @@ impl<'a> Tokenizer<'a> {
                 }
                 '<' => {
                     self.advance();
-                    if let Some(&'=') = self.input.peek() {
-                        self.advance();
-                        Token::LessThanOrEqual
-                    } else {
-                        Token::LessThan
+                    match self.input.peek() {
+                        Some(&'=') => {
+                            self.advance();
+                            Token::LessThanOrEqual
+                        }
+                        Some(&'>') => {
+                            self.advance();
+                            Token::NotEqual
+                        }
+                        _ => Token::LessThan,
                     }
                 }
                 '=' => {
                     self.advance();
+                    // `==` is accepted as a single equality operator, like SQLite does
+                    if let Some(&'=') = self.input.peek() {
+                        self.advance();
+                    }
                     Token::Equal
                 }
                 '!' => {
@@ -93,7 +408,8 @@ impl<'a> Tokenizer<'a> {
                 }
                 '*' => {
                     self.advance();
-                    if self.is_after_select && tokens.last().map_or(true, |t| 
+                    let previous = tokens.iter().rev().find(|t| !matches!(t, Token::Comment(_)));
+                    if self.is_after_select && previous.is_none_or(|t| 
                         matches!(t, Token::Keyword(Keyword::Select)) || matches!(t, Token::Comma)
                     ) {
                         Token::Wildcard
@@ -105,9 +421,21 @@ impl<'a> Tokenizer<'a> {
                     self.advance();
                     Token::Divide
                 }
+                '^' => {
+                    self.advance();
+                    Token::Caret
+                }
                 '-' => {
                     self.advance();
-                    Token::Minus
+                    if let Some(&'-') = self.input.peek() {
+                        let text = self.read_line_comment();
+                        if !self.keep_comments {
+                            continue;
+                        }
+                        Token::Comment(text)
+                    } else {
+                        Token::Minus
+                    }
                 }
                 '+' => {
                     self.advance();
@@ -117,79 +445,75 @@ impl<'a> Tokenizer<'a> {
                     self.advance();
                     Token::Comma
                 }
+                '.' => {
+                    self.advance();
+                    Token::Dot
+                }
                 ';' => {
                     self.advance();
                     Token::Semicolon
                 }
-                '\'' | '"' => self.tokenize_string(c)?,
-                '0'..='9' => {
-                    let mut number = 0u64;
-                    while let Some(&c) = self.input.peek() {
-                        if !c.is_ascii_digit() {
-                            break;
-                        }
-                        if let Some(new_number) = number.checked_mul(10).and_then(|n| n.checked_add(c.to_digit(10).unwrap() as u64)) {
-                            number = new_number;
-                        } else {
-                            return Err(Error::LexerError("Number too large".to_string()));
-                        }
-                        self.advance();
-                    }
-                    Token::Number(number)
+                '?' => {
+                    self.advance();
+                    self.tokenize_placeholder()?
                 }
+                '$' => match self.dollar_quote_tag() {
+                    Some(tag) => self.tokenize_dollar_quoted_string(&tag)?,
+                    None => self.tokenize_named_parameter(c)?,
+                },
+                ':' => self.tokenize_named_parameter(c)?,
+                '@' => self.tokenize_variable()?,
+                c if self.dialect.identifier_quote() == Some(c) => self.tokenize_quoted_identifier(c)?,
+                '\'' | '"' => self.tokenize_string(c, self.backslash_escapes)?,
+                // Postgres `E'...'` turns on backslash escapes for a single string
+                'E' | 'e' if self.input.clone().nth(1) == Some('\'') => {
+                    self.advance();
+                    self.tokenize_string('\'', true)?
+                }
+                '0'..='9' => self.tokenize_number()?,
                 'A'..='Z' | 'a'..='z' | '_' => {
                     let mut identifier = String::new();
                     while let Some(&c) = self.input.peek() {
                         if !c.is_ascii_alphanumeric() && c != '_' {
                             break;
                         }
+                        self.check_identifier_length(identifier.len() + 1)?;
                         identifier.push(c.to_ascii_uppercase());
                         self.advance();
                     }
                     
-                    match identifier.as_str() {
-                        "SELECT" => {
+                    match lookup_keyword(&identifier) {
+                        Some(Keyword::Select) => {
                             self.is_after_select = true;
                             Token::Keyword(Keyword::Select)
                         }
-                        "CREATE" => Token::Keyword(Keyword::Create),
-                        "TABLE" => Token::Keyword(Keyword::Table),
-                        "WHERE" => Token::Keyword(Keyword::Where),
-                        "ORDER" => Token::Keyword(Keyword::Order),
-                        "BY" => Token::Keyword(Keyword::By),
-                        "ASC" => Token::Keyword(Keyword::Asc),
-                        "DESC" => Token::Keyword(Keyword::Desc),
-                        "FROM" => {
+                        Some(Keyword::From) => {
                             self.is_after_select = false;
                             Token::Keyword(Keyword::From)
                         }
-                        "AND" => Token::Keyword(Keyword::And),
-                        "OR" => Token::Keyword(Keyword::Or),
-                        "NOT" => Token::Keyword(Keyword::Not),
-                        "TRUE" => Token::Keyword(Keyword::True),
-                        "FALSE" => Token::Keyword(Keyword::False),
-                        "PRIMARY" => Token::Keyword(Keyword::Primary),
-                        "KEY" => Token::Keyword(Keyword::Key),
-                        "FOREIGN" => Token::Keyword(Keyword::Foreign),
-                        "REFERENCES" => Token::Keyword(Keyword::References),
-                        "CHECK" => Token::Keyword(Keyword::Check),
-                        "INT" => Token::Keyword(Keyword::Int),
-                        "BOOL" => Token::Keyword(Keyword::Bool),
-                        "VARCHAR" => Token::Keyword(Keyword::Varchar),
-                        "NULL" => Token::Keyword(Keyword::Null),
-                        "INDEX" => Token::Keyword(Keyword::Index),
-                        "UNIQUE" => Token::Keyword(Keyword::Unique),
-                        "ON" => Token::Keyword(Keyword::On),
-                        _ => Token::Identifier(identifier),
+                        Some(keyword) => Token::Keyword(keyword),
+                        None => Token::Identifier(identifier),
+                    }
+                }
+                c => {
+                    let error = Error::LexerError(format!("Invalid character: {}", c));
+                    match skipped.as_deref_mut() {
+                        Some(errors) => {
+                            errors.push(error);
+                            self.advance();
+                            continue;
+                        }
+                        None => return Err(error),
                     }
                 }
-                c => return Err(Error::LexerError(format!("Invalid character: {}", c))),
             };
             tokens.push(token);
+            self.spans.push(span);
         }
         
         tokens.push(Token::Eof);
-        Ok(tokens)
+        self.spans.push(self.span());
+        Ok(())
     }
 }
 
@@ -246,6 +570,30 @@ mod tests {
         assert_eq!(tokens[1], Token::LessThanOrEqual);
         assert_eq!(tokens[2], Token::NotEqual);
         assert_eq!(tokens[3], Token::Equal);
+        assert_eq!(tokens[4], Token::Eof);
+    }
+
+    #[test]
+    fn test_double_equal() {
+        let mut tokenizer = Tokenizer::new("a == b = c");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Identifier("A".to_string()),
+            Token::Equal,
+            Token::Identifier("B".to_string()),
+            Token::Equal,
+            Token::Identifier("C".to_string()),
+            Token::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_sql_not_equal() {
+        let mut tokenizer = Tokenizer::new("a <> b <= c < d");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::NotEqual);
+        assert_eq!(tokens[3], Token::LessThanOrEqual);
+        assert_eq!(tokens[5], Token::LessThan);
     }
 
     #[test]
@@ -258,6 +606,54 @@ mod tests {
         assert_eq!(tokens[3], Token::Number(9999));
     }
 
+    #[test]
+    fn test_hex_numbers() {
+        let mut tokenizer = Tokenizer::new("0xFF 0x1a 0X10 0");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Number(255));
+        assert_eq!(tokens[1], Token::Number(26));
+        assert_eq!(tokens[2], Token::Number(16));
+        assert_eq!(tokens[3], Token::Number(0));
+    }
+
+    #[test]
+    fn test_error_empty_hex() {
+        let mut tokenizer = Tokenizer::new("SELECT 0x FROM users");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_error_hex_too_large() {
+        let mut tokenizer = Tokenizer::new("0x10000000000000000");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let mut tokenizer = Tokenizer::new("? ?1 ?12");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Placeholder(None));
+        assert_eq!(tokens[1], Token::Placeholder(Some(1)));
+        assert_eq!(tokens[2], Token::Placeholder(Some(12)));
+    }
+
+    #[test]
+    fn test_named_parameters() {
+        let mut tokenizer = Tokenizer::new(":user_id $name $1");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::NamedParameter(":user_id".to_string()));
+        assert_eq!(tokens[1], Token::NamedParameter("$name".to_string()));
+        assert_eq!(tokens[2], Token::NamedParameter("$1".to_string()));
+    }
+
+    #[test]
+    fn test_error_empty_named_parameter() {
+        let mut tokenizer = Tokenizer::new("SELECT id FROM users WHERE id = :");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+        let mut tokenizer = Tokenizer::new("SELECT id FROM users WHERE id = $ ");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
     #[test]
     fn test_strings() {
         let mut tokenizer = Tokenizer::new("'hello' \"world\"");
@@ -266,6 +662,23 @@ mod tests {
         assert_eq!(tokens[1], Token::String("world".to_string()));
     }
 
+    #[test]
+    fn test_dollar_quoted_strings() {
+        let mut tokenizer = Tokenizer::new("$$it's fine$$ $fn$ body with $$ and $ inside $fn$ $name");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::String("it's fine".to_string()));
+        assert_eq!(tokens[1], Token::String(" body with $$ and $ inside ".to_string()));
+        assert_eq!(tokens[2], Token::NamedParameter("$name".to_string()));
+    }
+
+    #[test]
+    fn test_error_unterminated_dollar_quote() {
+        let mut tokenizer = Tokenizer::new("SELECT $$never closed FROM t");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(msg)) if msg.contains("$$")));
+        let mut tokenizer = Tokenizer::new("SELECT $a$ closed by the wrong tag $b$");
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(msg)) if msg.contains("$a$")));
+    }
+
     #[test]
     fn test_keywords() {
         let mut tokenizer = Tokenizer::new("SELECT FROM WHERE ORDER BY CREATE TABLE INT VARCHAR BOOL");
@@ -282,6 +695,15 @@ mod tests {
         assert_eq!(tokens[9], Token::Keyword(Keyword::Bool));
     }
 
+    #[test]
+    fn test_boolean_alias() {
+        let mut tokenizer = Tokenizer::new("BOOL boolean Boolean");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Keyword(Keyword::Bool));
+        assert_eq!(tokens[1], Token::Keyword(Keyword::Bool));
+        assert_eq!(tokens[2], Token::Keyword(Keyword::Bool));
+    }
+
     #[test]
     fn test_identifiers() {
         let mut tokenizer = Tokenizer::new("username age_2 first_name _temp");
@@ -328,6 +750,50 @@ mod tests {
     }
 
     #[test]
+    fn test_limits() {
+        let mut tokenizer = Tokenizer::new("abcd 1234 0xff").with_limits(4, 4);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Identifier("ABCD".to_string()));
+        assert_eq!(tokens[1], Token::Number(1234));
+        assert_eq!(tokens[2], Token::Number(255));
+    }
+
+    #[test]
+    fn test_error_identifier_over_limit() {
+        let mut tokenizer = Tokenizer::new("SELECT abcde FROM t").with_limits(4, 4);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+        let mut tokenizer = Tokenizer::new("SELECT :abcde FROM t").with_limits(4, 4);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+
+        let mut tokenizer = Tokenizer::new_with_dialect("\"abcd\"", Dialect::Postgres).with_limits(4, 4);
+        assert_eq!(tokenizer.tokenize().unwrap()[0], Token::Identifier("abcd".to_string()));
+        let mut tokenizer = Tokenizer::new_with_dialect("\"abcde\"", Dialect::Postgres).with_limits(4, 4);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_error_number_over_limit() {
+        let mut tokenizer = Tokenizer::new("SELECT 00012 FROM t").with_limits(4, 4);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+        let mut tokenizer = Tokenizer::new("SELECT 0x12345 FROM t").with_limits(4, 4);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_spans() {
+        let mut tokenizer = Tokenizer::new("SELECT id\n  FROM users");
+        let tokens = tokenizer.tokenize().unwrap();
+        let spans = tokenizer.spans();
+        assert_eq!(spans.len(), tokens.len());
+        assert_eq!(spans[0], Span { line: 1, column: 1 });
+        assert_eq!(spans[1], Span { line: 1, column: 8 });
+        assert_eq!(spans[2], Span { line: 2, column: 3 });
+        assert_eq!(spans[3], Span { line: 2, column: 8 });
+        assert_eq!(spans[4], Span { line: 2, column: 13 });
+    }
+
+    #[test]
+    #[allow(clippy::while_let_on_iterator)]
     fn test_iterator() {
         let mut tokenizer = Tokenizer::new("SELECT id FROM users");
         let mut tokens = Vec::new();
@@ -340,4 +806,175 @@ mod tests {
         assert_eq!(tokens[3], Token::Identifier("USERS".to_string()));
         assert_eq!(tokens[4], Token::Eof);
     }
+
+    #[test]
+    fn test_backslash_escapes_enabled() {
+        let mut tokenizer = Tokenizer::new(r"'\n' 'a\tb' 'c\\d' 'it\'s'").with_backslash_escapes(true);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::String("\n".to_string()));
+        assert_eq!(tokens[1], Token::String("a\tb".to_string()));
+        assert_eq!(tokens[2], Token::String("c\\d".to_string()));
+        assert_eq!(tokens[3], Token::String("it's".to_string()));
+    }
+
+    #[test]
+    fn test_backslash_is_literal_by_default() {
+        let mut tokenizer = Tokenizer::new(r"'\n'");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::String("\\n".to_string()));
+    }
+
+    #[test]
+    fn test_escape_string_prefix() {
+        let mut tokenizer = Tokenizer::new(r"E'\t' e'it\'s' email 'a\n' E");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::String("\t".to_string()));
+        assert_eq!(tokens[1], Token::String("it's".to_string()));
+        assert_eq!(tokens[2], Token::Identifier("EMAIL".to_string()));
+        assert_eq!(tokens[3], Token::String("a\\n".to_string()));
+        assert_eq!(tokens[4], Token::Identifier("E".to_string()));
+    }
+
+    #[test]
+    fn test_trailing_backslash_in_string_errors() {
+        let mut tokenizer = Tokenizer::new(r"'abc\").with_backslash_escapes(true);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(crate::token::is_keyword("SELECT"));
+        assert!(crate::token::is_keyword("select"));
+        assert!(!crate::token::is_keyword("users"));
+        assert_eq!(Keyword::AutoIncrement.as_str(), "AUTO_INCREMENT");
+    }
+
+    #[test]
+    fn test_all_keywords_are_tokenized_as_keywords() {
+        for word in crate::token::all_keywords() {
+            let mut tokenizer = Tokenizer::new(word);
+            let tokens = tokenizer.tokenize().unwrap();
+            assert!(matches!(tokens[0], Token::Keyword(_)), "{} was not tokenized as a keyword", word);
+        }
+    }
+
+    #[test]
+    fn test_dialect_identifier_quotes() {
+        let input = "SELECT \"Order\" FROM t";
+
+        let mut tokenizer = Tokenizer::new_with_dialect(input, Dialect::Postgres);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::Identifier("Order".to_string()));
+
+        let mut tokenizer = Tokenizer::new_with_dialect(input, Dialect::MySql);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::String("Order".to_string()));
+
+        let mut tokenizer = Tokenizer::new_with_dialect("SELECT `Order` FROM t", Dialect::MySql);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::Identifier("Order".to_string()));
+
+        let mut tokenizer = Tokenizer::new_with_dialect("SELECT `Order` FROM t", Dialect::Postgres);
+        assert!(matches!(tokenizer.tokenize(), Err(Error::LexerError(_))));
+    }
+
+    #[test]
+    fn test_doubled_quote_in_string() {
+        let mut tokenizer = Tokenizer::new("'it''s' \"say \"\"hi\"\"\" ''");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::String("it's".to_string()));
+        assert_eq!(tokens[1], Token::String("say \"hi\"".to_string()));
+        assert_eq!(tokens[2], Token::String(String::new()));
+    }
+
+    #[test]
+    fn test_quoted_identifier_errors_and_escapes() {
+        let mut tokenizer = Tokenizer::new_with_dialect("\"a\"\"b\"", Dialect::Postgres);
+        assert_eq!(tokenizer.tokenize().unwrap()[0], Token::Identifier("a\"b".to_string()));
+
+        let mut tokenizer = Tokenizer::new_with_dialect("\"\"", Dialect::Postgres);
+        assert!(tokenizer.tokenize().is_err());
+
+        let mut tokenizer = Tokenizer::new_with_dialect("`abc", Dialect::MySql);
+        assert!(tokenizer.tokenize().is_err());
+    }
+
+    #[test]
+    fn test_token_count() {
+        assert_eq!(crate::token_count("SELECT a + 1 FROM t WHERE b > 2;").unwrap(), 11);
+        assert_eq!(crate::token_count("").unwrap(), 0);
+        assert!(crate::token_count("SELECT #").is_err());
+    }
+
+    #[test]
+    fn test_line_comments() {
+        let mut tokenizer = Tokenizer::new("SELECT a -- the column\nFROM t - 1");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens[2], Token::Keyword(Keyword::From));
+        assert_eq!(tokens[4], Token::Minus);
+        assert_eq!(tokenizer.spans()[2], Span { line: 2, column: 1 });
+    }
+
+    #[test]
+    fn test_line_comment_at_end_of_input() {
+        let mut tokenizer = Tokenizer::new("SELECT 1; -- done");
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Keyword(Keyword::Select),
+            Token::Number(1),
+            Token::Semicolon,
+            Token::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_keep_comments() {
+        let mut tokenizer = Tokenizer::new("SELECT * -- hi\nFROM t").with_keep_comments(true);
+        let tokens = tokenizer.tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Keyword(Keyword::Select),
+            Token::Wildcard,
+            Token::Comment(" hi".to_string()),
+            Token::Keyword(Keyword::From),
+            Token::Identifier("T".to_string()),
+            Token::Eof,
+        ]);
+        assert_eq!(tokenizer.spans()[2], Span { line: 1, column: 10 });
+    }
+
+    #[test]
+    fn test_tokenize_lossy() {
+        let mut tokenizer = Tokenizer::new("SELECT a # b FROM t ~;");
+        let (tokens, errors) = tokenizer.tokenize_lossy();
+        assert_eq!(tokens, vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier("A".to_string()),
+            Token::Identifier("B".to_string()),
+            Token::Keyword(Keyword::From),
+            Token::Identifier("T".to_string()),
+            Token::Semicolon,
+            Token::Eof,
+        ]);
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], Error::LexerError(msg) if msg == "Invalid character: #"));
+        assert!(matches!(&errors[1], Error::LexerError(msg) if msg == "Invalid character: ~"));
+        assert_eq!(tokenizer.spans().len(), tokens.len());
+
+        // Other errors still end tokenization
+        let (tokens, errors) = Tokenizer::new("a 'open").tokenize_lossy();
+        assert_eq!(tokens, vec![Token::Identifier("A".to_string()), Token::Eof]);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_variables() {
+        let tokens = Tokenizer::new("@counter @Max_2").tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Variable("counter".to_string()));
+        assert_eq!(tokens[1], Token::Variable("Max_2".to_string()));
+        assert!(matches!(Tokenizer::new("@ x").tokenize(), Err(Error::LexerError(_))));
+
+        let tokens = Tokenizer::new("@abcdef").with_limits(6, 4).tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Variable("abcdef".to_string()));
+        assert!(matches!(Tokenizer::new("@abcdefg").with_limits(6, 4).tokenize(), Err(Error::LexerError(_))));
+    }
 }